@@ -1,8 +1,9 @@
 pub mod myarchitecture {
     use core::fmt;
 
-    use crate::numtype::mynumtype::NumType;
+    use crate::{numtype::mynumtype::NumType, types::mytype::align_to};
 
+    #[derive(Clone, Copy)]
     pub enum Register {
         /// rax, x0
         R0,
@@ -22,7 +23,7 @@ pub mod myarchitecture {
         R7,
         /// r8, x8(rbp)
         R8,
-        /// r9, x9(tsp)
+        /// r9, x9
         R9,
         /// r10, x10
         R10,
@@ -38,6 +39,7 @@ pub mod myarchitecture {
         R15,
     }
 
+    #[derive(Clone)]
     pub enum Operand {
         Num(NumType),
         Register(Register),
@@ -92,6 +94,48 @@ pub mod myarchitecture {
         }
     }
 
+    impl Register {
+        /// sizeバイトの値を扱うときのレジスタ名
+        #[cfg(target_arch = "x86_64")]
+        fn sized(&self, size: usize) -> String {
+            let names = match self {
+                Register::R0 => ["al", "ax", "eax"],
+                Register::R1 => ["dil", "di", "edi"],
+                Register::R2 => ["sil", "si", "esi"],
+                Register::R3 => ["dl", "dx", "edx"],
+                Register::R4 => ["cl", "cx", "ecx"],
+                Register::R5 => ["bpl", "bp", "ebp"],
+                Register::R6 => ["spl", "sp", "esp"],
+                Register::R7 => ["bl", "bx", "ebx"],
+                Register::R8 => ["r8b", "r8w", "r8d"],
+                Register::R9 => ["r9b", "r9w", "r9d"],
+                Register::R10 => ["r10b", "r10w", "r10d"],
+                Register::R11 => ["r11b", "r11w", "r11d"],
+                Register::R12 => ["r12b", "r12w", "r12d"],
+                Register::R13 => ["r13b", "r13w", "r13d"],
+                Register::R14 => ["r14b", "r14w", "r14d"],
+                Register::R15 => ["r15b", "r15w", "r15d"],
+            };
+            match size {
+                1 => names[0].to_string(),
+                2 => names[1].to_string(),
+                4 => names[2].to_string(),
+                _ => self.to_string(),
+            }
+        }
+
+        /// sizeバイトの値を扱うときのレジスタ名
+        /// 4バイト以下はwレジスタを使う
+        #[cfg(target_arch = "aarch64")]
+        fn sized(&self, size: usize) -> String {
+            let name = self.to_string();
+            match name.strip_prefix('x') {
+                Some(n) if size <= 4 => format!("w{}", n),
+                _ => name,
+            }
+        }
+    }
+
     impl fmt::Display for Operand {
         #[cfg(target_arch = "x86_64")]
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -124,21 +168,139 @@ pub mod myarchitecture {
         ".text\n".to_string()
     }
 
+    // 出力先のセクション
+    pub enum Section {
+        Text,
+        // 初期値のあるグローバル変数
+        Data,
+        // 初期値のないグローバル変数
+        // 実行時に0で埋められる
+        Bss,
+        // 文字列リテラルなど書き換えない値
+        Rodata,
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    pub fn section(section: Section) -> String {
+        match section {
+            Section::Text => ".text\n".to_string(),
+            Section::Data => ".data\n".to_string(),
+            Section::Bss => ".bss\n".to_string(),
+            Section::Rodata => ".section .rodata\n".to_string(),
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    pub fn section(section: Section) -> String {
+        match section {
+            Section::Text => ".text\n".to_string(),
+            Section::Data => ".data\n".to_string(),
+            Section::Bss => ".bss\n".to_string(),
+            Section::Rodata => ".section __TEXT,__const\n".to_string(),
+        }
+    }
+
+    // 文字列リテラルのラベル
+    // 外から見えないローカルなラベルにする
     #[cfg(target_arch = "x86_64")]
-    pub fn main_func() -> String {
-        ".globl main\nmain:\n".to_string()
+    pub fn string_label(id: usize) -> String {
+        format!(".L.str.{}", id)
     }
 
     #[cfg(target_arch = "aarch64")]
-    pub fn main_func() -> String {
-        ".globl _main\n_main:\n".to_string()
+    pub fn string_label(id: usize) -> String {
+        format!("l_.str.{}", id)
+    }
+
+    /// .globl name
+    /// .p2align log2(align)
+    /// name:
+    pub fn data_label(name: &str, align: usize) -> String {
+        let symbol = symbol_name(name);
+        format!(
+            ".globl {}\n.p2align {}\n{}:\n",
+            symbol,
+            align.trailing_zeros(),
+            symbol
+        )
+    }
+
+    /// .byte, .short, .long, .quad value
+    pub fn data_value(value: i64, size: usize) -> String {
+        let directive = match size {
+            1 => ".byte",
+            2 => ".short",
+            4 => ".long",
+            _ => ".quad",
+        };
+        format!("\t{} {}\n", directive, value)
+    }
+
+    /// .byte b0, b1, ...
+    pub fn data_bytes(bytes: &[u8]) -> String {
+        let values: Vec<String> = bytes.iter().map(|b| b.to_string()).collect();
+        format!("\t.byte {}\n", values.join(", "))
+    }
+
+    /// .zero size
+    pub fn data_zero(size: usize) -> String {
+        format!("\t.zero {}\n", size)
+    }
+
+    /// 引数を渡すレジスタ
+    /// rdi, rsi, rdx, rcx, r8, r9
+    #[cfg(target_arch = "x86_64")]
+    pub const PARAM_REGISTERS: [Register; 6] = [
+        Register::R1,
+        Register::R2,
+        Register::R3,
+        Register::R4,
+        Register::R8,
+        Register::R9,
+    ];
+
+    /// 引数を渡すレジスタ
+    /// x0 - x7
+    #[cfg(target_arch = "aarch64")]
+    pub const PARAM_REGISTERS: [Register; 8] = [
+        Register::R0,
+        Register::R1,
+        Register::R2,
+        Register::R3,
+        Register::R4,
+        Register::R5,
+        Register::R6,
+        Register::R7,
+    ];
+
+    #[cfg(target_arch = "x86_64")]
+    fn symbol_name(name: &str) -> String {
+        name.to_string()
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn symbol_name(name: &str) -> String {
+        format!("_{}", name)
+    }
+
+    /// .globl name
+    /// name:
+    /// (memory_allocate)
+    pub fn function_prologue(name: &str, stack_size: usize) -> String {
+        let symbol = symbol_name(name);
+        format!(
+            ".globl {}\n{}:\n{}",
+            symbol,
+            symbol,
+            memory_allocate(align_to(stack_size, 16))
+        )
     }
 
     /// push rbp
     /// mov rbp, rsp
     /// sub rsp, #bytes
     #[cfg(target_arch = "x86_64")]
-    pub fn memory_allocate(bytes: usize) -> String {
+    fn memory_allocate(bytes: usize) -> String {
         format!(
             "{}{}{}",
             push(Operand::Register(Register::R5)),
@@ -150,40 +312,335 @@ pub mod myarchitecture {
         )
     }
 
-    /// mov rbp(r8), sp(r13)
-    /// mov rsp(r9), sp(r13)
-    /// push rbp(r8)
-    /// mov rbp(r8), rsp(r9)
-    /// sub rsp(r9), $bytes
+    /// stp x29, lr, [sp, #-16]!
+    /// mov x29, sp
+    /// mov rbp(r8), x29
+    /// sub sp, sp, #bytes
+    /// 4096バイト以上のフレームはx9を介して確保する
     #[cfg(target_arch = "aarch64")]
-    pub fn memory_allocate(bytes: usize) -> String {
+    fn memory_allocate(bytes: usize) -> String {
         format!(
-            "{}{}{}{}{}",
+            "\tstp x29, {}, [{}, #-16]!\n\tmov x29, {}\n\tmov {}, x29\n{}",
+            Register::R14,
+            Register::R13,
+            Register::R13,
+            Register::R8,
+            sub(Operand::Register(Register::R13), Operand::Num(bytes))
+        )
+    }
+
+    /// movsx rd, byte ptr [addr]
+    /// movsx rd, word ptr [addr]
+    /// movsxd rd, dword ptr [addr]
+    /// mov rd, [addr]
+    /// 8バイトより小さい値は符号拡張して読む
+    /// 符号なしの場合はmovzxなどでゼロ拡張する
+    #[cfg(target_arch = "x86_64")]
+    fn load_sized(rd: Register, addr: Register, size: usize, signed: bool) -> String {
+        match (size, signed) {
+            (1, true) => format!("\tmovsx {}, byte ptr [{}]\n", rd, addr),
+            (2, true) => format!("\tmovsx {}, word ptr [{}]\n", rd, addr),
+            (4, true) => format!("\tmovsxd {}, dword ptr [{}]\n", rd, addr),
+            (1, false) => format!("\tmovzx {}, byte ptr [{}]\n", rd.sized(4), addr),
+            (2, false) => format!("\tmovzx {}, word ptr [{}]\n", rd.sized(4), addr),
+            // 32ビットレジスタへの書き込みは上位32ビットを0にする
+            (4, false) => format!("\tmov {}, dword ptr [{}]\n", rd.sized(4), addr),
+            _ => mov(Operand::Register(rd), Operand::Address(addr)),
+        }
+    }
+
+    /// ldrsb rd, [addr]
+    /// ldrsh rd, [addr]
+    /// ldrsw rd, [addr]
+    /// ldr rd, [addr]
+    /// 8バイトより小さい値は符号拡張して読む
+    /// 符号なしの場合はldrb, ldrh, ldr wdでゼロ拡張する
+    #[cfg(target_arch = "aarch64")]
+    fn load_sized(rd: Register, addr: Register, size: usize, signed: bool) -> String {
+        match (size, signed) {
+            (1, true) => format!("\tldrsb {}, [{}]\n", rd, addr),
+            (2, true) => format!("\tldrsh {}, [{}]\n", rd, addr),
+            (4, true) => format!("\tldrsw {}, [{}]\n", rd, addr),
+            (1, false) => format!("\tldrb {}, [{}]\n", rd.sized(4), addr),
+            (2, false) => format!("\tldrh {}, [{}]\n", rd.sized(4), addr),
+            (4, false) => format!("\tldr {}, [{}]\n", rd.sized(4), addr),
+            _ => ldr(Operand::Register(rd), Operand::Address(addr)),
+        }
+    }
+
+    /// movsx rd, rd(sizeバイト分)
+    /// movzx rd, rd(sizeバイト分)
+    /// レジスタの下位sizeバイトを型に合わせて64ビットに広げる
+    #[cfg(target_arch = "x86_64")]
+    fn extend_sized(rd: Register, size: usize, signed: bool) -> String {
+        match (size, signed) {
+            (1 | 2, true) => format!("\tmovsx {}, {}\n", rd, rd.sized(size)),
+            (4, true) => format!("\tmovsxd {}, {}\n", rd, rd.sized(4)),
+            (1 | 2, false) => format!("\tmovzx {}, {}\n", rd.sized(4), rd.sized(size)),
+            (4, false) => format!("\tmov {}, {}\n", rd.sized(4), rd.sized(4)),
+            _ => String::new(),
+        }
+    }
+
+    /// sxtb, sxth, sxtw
+    /// uxtb, uxth, mov wd, wd
+    #[cfg(target_arch = "aarch64")]
+    fn extend_sized(rd: Register, size: usize, signed: bool) -> String {
+        match (size, signed) {
+            (1, true) => format!("\tsxtb {}, {}\n", rd, rd.sized(4)),
+            (2, true) => format!("\tsxth {}, {}\n", rd, rd.sized(4)),
+            (4, true) => format!("\tsxtw {}, {}\n", rd, rd.sized(4)),
+            (1, false) => format!("\tuxtb {}, {}\n", rd.sized(4), rd.sized(4)),
+            (2, false) => format!("\tuxth {}, {}\n", rd.sized(4), rd.sized(4)),
+            (4, false) => format!("\tmov {}, {}\n", rd.sized(4), rd.sized(4)),
+            _ => String::new(),
+        }
+    }
+
+    /// mov [addr], rs(sizeバイト分)
+    #[cfg(target_arch = "x86_64")]
+    fn store_sized(addr: Register, rs: Register, size: usize) -> String {
+        format!("\tmov [{}], {}\n", addr, rs.sized(size))
+    }
+
+    /// strb ws, [addr]
+    /// strh ws, [addr]
+    /// str ws, [addr]
+    /// str xs, [addr]
+    #[cfg(target_arch = "aarch64")]
+    fn store_sized(addr: Register, rs: Register, size: usize) -> String {
+        let op = match size {
+            1 => "strb",
+            2 => "strh",
+            _ => "str",
+        };
+        format!("\t{} {}, [{}]\n", op, rs.sized(size), addr)
+    }
+
+    /// mov rax, rbp
+    /// sub rax, offset
+    /// mov [rax], reg
+    #[cfg(target_arch = "x86_64")]
+    pub fn store_param(reg: Register, offset: usize, size: usize) -> String {
+        format!(
+            "{}{}{}",
+            mov(
+                Operand::Register(Register::R0),
+                Operand::Register(Register::R5)
+            ),
+            sub(Operand::Register(Register::R0), Operand::Num(offset)),
+            store_sized(Register::R0, reg, size)
+        )
+    }
+
+    /// mov x10, rbp(r8)
+    /// sub x10, offset
+    /// str reg, [x10]
+    #[cfg(target_arch = "aarch64")]
+    pub fn store_param(reg: Register, offset: usize, size: usize) -> String {
+        format!(
+            "{}{}{}",
             mov(
-                Operand::Register(Register::R8),
-                Operand::Register(Register::R13)
+                Operand::Register(Register::R10),
+                Operand::Register(Register::R8)
+            ),
+            sub(Operand::Register(Register::R10), Operand::Num(offset)),
+            store_sized(Register::R10, reg, size)
+        )
+    }
+
+    /// mov rax, rbp
+    /// add rax, 16 + 8 * index
+    /// mov rax, [rax]
+    /// mov r10, rbp
+    /// sub r10, offset
+    /// mov [r10], rax
+    /// スタックで渡された引数はリターンアドレスと退避したrbpの上にある
+    #[cfg(target_arch = "x86_64")]
+    pub fn store_stack_param(index: usize, offset: usize, size: usize) -> String {
+        format!(
+            "{}{}{}{}{}{}",
+            mov(
+                Operand::Register(Register::R0),
+                Operand::Register(Register::R5)
+            ),
+            add(
+                Operand::Register(Register::R0),
+                Operand::Num(16 + 8 * index)
             ),
             mov(
-                Operand::Register(Register::R9),
-                Operand::Register(Register::R13)
+                Operand::Register(Register::R0),
+                Operand::Address(Register::R0)
             ),
-            push(Operand::Register(Register::R8)),
             mov(
-                Operand::Register(Register::R8),
-                Operand::Register(Register::R9)
+                Operand::Register(Register::R10),
+                Operand::Register(Register::R5)
             ),
-            sub(Operand::Register(Register::R9), Operand::Num(bytes))
+            sub(Operand::Register(Register::R10), Operand::Num(offset)),
+            store_sized(Register::R10, Register::R0, size)
         )
     }
 
+    /// mov x10, rbp(r8)
+    /// add x10, 16 + 8 * index
+    /// ldr x10, [x10]
+    /// mov x11, rbp(r8)
+    /// sub x11, offset
+    /// str x10, [x11]
+    /// スタックで渡された引数は退避したx29, lrの上にある
     #[cfg(target_arch = "aarch64")]
-    fn ldr(rd: Operand, rn: Operand) -> String {
-        format!("\tldr {}, {}\n", rd, rn)
+    pub fn store_stack_param(index: usize, offset: usize, size: usize) -> String {
+        format!(
+            "{}{}{}{}{}{}",
+            mov(
+                Operand::Register(Register::R10),
+                Operand::Register(Register::R8)
+            ),
+            add(
+                Operand::Register(Register::R10),
+                Operand::Num(16 + 8 * index)
+            ),
+            ldr(
+                Operand::Register(Register::R10),
+                Operand::Address(Register::R10)
+            ),
+            mov(
+                Operand::Register(Register::R11),
+                Operand::Register(Register::R8)
+            ),
+            sub(Operand::Register(Register::R11), Operand::Num(offset)),
+            store_sized(Register::R11, Register::R10, size)
+        )
+    }
+
+    /// mov rax, rsp
+    /// and rsp, -16
+    /// push rax
+    /// (sub rsp, 8)
+    /// 関数呼び出しの時点でrspが16の倍数になるよう、
+    /// スタックで渡す引数の個数に合わせて詰め物をする
+    #[cfg(target_arch = "x86_64")]
+    pub fn align_call_stack(stack_args: usize) -> String {
+        let padding = if stack_args.is_multiple_of(2) {
+            sub(Operand::Register(Register::R6), Operand::Num(8))
+        } else {
+            String::new()
+        };
+        format!(
+            "{}\tand {}, -16\n{}{}",
+            mov(
+                Operand::Register(Register::R0),
+                Operand::Register(Register::R6)
+            ),
+            Register::R6,
+            push(Operand::Register(Register::R0)),
+            padding
+        )
+    }
+
+    /// スタックは常に16バイト単位で積んでいるので調整は不要
+    #[cfg(target_arch = "aarch64")]
+    pub fn align_call_stack(_stack_args: usize) -> String {
+        String::new()
+    }
+
+    /// pop reg
+    pub fn pop_param(reg: Register) -> String {
+        pop(Operand::Register(reg))
+    }
+
+    /// pop r11, x11
+    /// 呼び出す先のアドレスは引数のレジスタと重ならないレジスタに置く
+    pub fn pop_callee() -> String {
+        pop(Operand::Register(Register::R11))
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    pub fn call(name: &str, stack_args: usize) -> String {
+        call_with(&format!("call {}", symbol_name(name)), stack_args)
+    }
+
+    /// call r11
+    /// AT&T記法の call *%r11 にあたる
+    #[cfg(target_arch = "x86_64")]
+    pub fn call_indirect(stack_args: usize) -> String {
+        call_with(&format!("call {}", Register::R11), stack_args)
+    }
+
+    /// mov rax, 0
+    /// call target
+    /// add rsp, 8 * stack_args (+ 8)
+    /// pop rsp
+    /// push rax
+    /// 可変長引数の関数のためにalにベクタレジスタの数(0)を入れておく
+    #[cfg(target_arch = "x86_64")]
+    fn call_with(instruction: &str, stack_args: usize) -> String {
+        let padding = if stack_args.is_multiple_of(2) { 8 } else { 0 };
+        format!(
+            "{}\t{}\n{}{}{}",
+            mov(Operand::Register(Register::R0), Operand::Num(0)),
+            instruction,
+            add(
+                Operand::Register(Register::R6),
+                Operand::Num(8 * stack_args + padding)
+            ),
+            pop(Operand::Register(Register::R6)),
+            push(Operand::Register(Register::R0))
+        )
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    pub fn call(name: &str, stack_args: usize) -> String {
+        call_with(&format!("bl {}", symbol_name(name)), stack_args)
+    }
+
+    /// blr x11
+    #[cfg(target_arch = "aarch64")]
+    pub fn call_indirect(stack_args: usize) -> String {
+        call_with(&format!("blr {}", Register::R11), stack_args)
+    }
+
+    /// sub sp, sp, #area
+    /// (ldr x10, [sp, #area + 16 * i]
+    ///  str x10, [sp, #8 * i])
+    /// bl name, blr x11
+    /// add sp, sp, #area + 16 * stack_args
+    /// mov rbp(r8), x29
+    /// push x0
+    /// スタックに16バイト単位で積んだ引数を、ABIに合わせて8バイト間隔に詰め直す
+    /// x8は呼び出し先で壊されうるのでx29から復元する
+    #[cfg(target_arch = "aarch64")]
+    fn call_with(instruction: &str, stack_args: usize) -> String {
+        let area = align_to(8 * stack_args, 16);
+        let mut copy = String::new();
+        for i in 0..stack_args {
+            copy.push_str(&format!(
+                "\tldr {}, [{}, #{}]\n\tstr {}, [{}, #{}]\n",
+                Register::R10,
+                Register::R13,
+                area + 16 * i,
+                Register::R10,
+                Register::R13,
+                8 * i
+            ));
+        }
+        format!(
+            "{}{}\t{}\n{}\tmov {}, x29\n{}",
+            sub(Operand::Register(Register::R13), Operand::Num(area)),
+            copy,
+            instruction,
+            add(
+                Operand::Register(Register::R13),
+                Operand::Num(area + 16 * stack_args)
+            ),
+            Register::R8,
+            push(Operand::Register(Register::R0))
+        )
     }
 
     #[cfg(target_arch = "aarch64")]
-    fn str(rd: Operand, rn: Operand) -> String {
-        format!("\tstr {}, {}\n", rd, rn)
+    fn ldr(rd: Operand, rn: Operand) -> String {
+        format!("\tldr {}, {}\n", rd, rn)
     }
 
     #[cfg(target_arch = "x86_64")]
@@ -196,13 +653,210 @@ pub mod myarchitecture {
         pop(Operand::Register(Register::R0))
     }
 
+    /// pop r0
+    /// jmp .L.return.func
+    pub fn return_stmt(func_name: &str) -> String {
+        format!(
+            "{}{}",
+            pop(Operand::Register(Register::R0)),
+            jmp(&return_label(func_name))
+        )
+    }
+
+    // 関数ごとに一つだけ置くエピローグへのラベル
+    pub fn return_label(func_name: &str) -> String {
+        format!(".L.return.{}", func_name)
+    }
+
+    pub fn label(name: &str) -> String {
+        format!("{}:\n", name)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    pub fn jmp(label: &str) -> String {
+        format!("\tjmp {}\n", label)
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    pub fn jmp(label: &str) -> String {
+        format!("\tb {}\n", label)
+    }
+
+    /// pop rax
+    /// cmp rax, 0
+    /// je label
+    #[cfg(target_arch = "x86_64")]
+    pub fn jump_if_zero(label: &str) -> String {
+        format!(
+            "{}\tcmp {}, 0\n\tje {}\n",
+            pop(Operand::Register(Register::R0)),
+            Register::R0,
+            label
+        )
+    }
+
+    /// pop x0
+    /// cbz x0, label
+    #[cfg(target_arch = "aarch64")]
+    pub fn jump_if_zero(label: &str) -> String {
+        format!(
+            "{}\tcbz {}, {}\n",
+            pop(Operand::Register(Register::R0)),
+            Register::R0,
+            label
+        )
+    }
+
+    /// pop rax
+    /// cmp rax, 0
+    /// jne label
+    #[cfg(target_arch = "x86_64")]
+    pub fn jump_if_not_zero(label: &str) -> String {
+        format!(
+            "{}\tcmp {}, 0\n\tjne {}\n",
+            pop(Operand::Register(Register::R0)),
+            Register::R0,
+            label
+        )
+    }
+
+    /// pop x0
+    /// cbnz x0, label
+    #[cfg(target_arch = "aarch64")]
+    pub fn jump_if_not_zero(label: &str) -> String {
+        format!(
+            "{}\tcbnz {}, {}\n",
+            pop(Operand::Register(Register::R0)),
+            Register::R0,
+            label
+        )
+    }
+
+    /// pop rax
+    /// switch文で比較する値をraxに置く
+    pub fn pop_switch_value() -> String {
+        pop(Operand::Register(Register::R0))
+    }
+
+    /// mov rdi, value
+    /// cmp rax, rdi
+    /// je label
+    #[cfg(target_arch = "x86_64")]
+    pub fn jump_if_equal(value: i64, label: &str) -> String {
+        format!(
+            "{}\tcmp {}, {}\n\tje {}\n",
+            mov(
+                Operand::Register(Register::R1),
+                Operand::Num(value as NumType)
+            ),
+            Register::R0,
+            Register::R1,
+            label
+        )
+    }
+
+    /// mov x1, value
+    /// cmp x0, x1
+    /// b.eq label
+    #[cfg(target_arch = "aarch64")]
+    pub fn jump_if_equal(value: i64, label: &str) -> String {
+        format!(
+            "{}\tcmp {}, {}\n\tb.eq {}\n",
+            mov_imm(Register::R1, value as NumType),
+            Register::R0,
+            Register::R1,
+            label
+        )
+    }
+
+    /// sub rax, min
+    /// cmp rax, len
+    /// jae default
+    /// lea rdi, [rip + table]
+    /// movsxd rax, dword ptr [rdi + rax * 4]
+    /// add rax, rdi
+    /// jmp rax
+    /// 表には表の先頭からの相対位置が入っている
+    #[cfg(target_arch = "x86_64")]
+    pub fn jump_table(min: i64, len: usize, table: &str, default: &str) -> String {
+        format!(
+            "{}\tsub {}, {}\n{}\tcmp {}, {}\n\tjae {}\n\tlea {}, [rip + {}]\n\tmovsxd {}, dword ptr [{} + {} * 4]\n\tadd {}, {}\n\tjmp {}\n",
+            mov(Operand::Register(Register::R1), Operand::Num(min as NumType)),
+            Register::R0,
+            Register::R1,
+            mov(Operand::Register(Register::R1), Operand::Num(len)),
+            Register::R0,
+            Register::R1,
+            default,
+            Register::R1,
+            table,
+            Register::R0,
+            Register::R1,
+            Register::R0,
+            Register::R0,
+            Register::R1,
+            Register::R0
+        )
+    }
+
+    /// sub x0, x0, min
+    /// cmp x0, len
+    /// b.hs default
+    /// adrp x1, table@PAGE
+    /// add x1, x1, table@PAGEOFF
+    /// ldrsw x2, [x1, x0, lsl #2]
+    /// add x1, x1, x2
+    /// br x1
+    #[cfg(target_arch = "aarch64")]
+    pub fn jump_table(min: i64, len: usize, table: &str, default: &str) -> String {
+        format!(
+            "{}\tsub {}, {}, {}\n{}\tcmp {}, {}\n\tb.hs {}\n\tadrp {}, {}@PAGE\n\tadd {}, {}, {}@PAGEOFF\n\tldrsw {}, [{}, {}, lsl #2]\n\tadd {}, {}, {}\n\tbr {}\n",
+            mov_imm(Register::R1, min as NumType),
+            Register::R0,
+            Register::R0,
+            Register::R1,
+            mov_imm(Register::R1, len),
+            Register::R0,
+            Register::R1,
+            default,
+            Register::R1,
+            table,
+            Register::R1,
+            Register::R1,
+            table,
+            Register::R2,
+            Register::R1,
+            Register::R0,
+            Register::R1,
+            Register::R1,
+            Register::R2,
+            Register::R1
+        )
+    }
+
+    /// .section .rodata
+    /// .p2align 2
+    /// table:
+    /// .long label - table ...
+    /// .text
+    pub fn jump_table_data(table: &str, labels: &[String]) -> String {
+        let mut asm = format!("{}.p2align 2\n{}", section(Section::Rodata), label(table));
+        for l in labels {
+            asm.push_str(&format!("\t.long {} - {}\n", l, table));
+        }
+        asm.push_str(&section(Section::Text));
+        asm
+    }
+
+    /// .L.return.name:
     /// mov rsp, rbp
     /// pop rbp
     /// ret
     #[cfg(target_arch = "x86_64")]
-    pub fn program_epilogue() -> String {
+    pub fn function_epilogue(name: &str) -> String {
         format!(
-            "{}{}{}",
+            "{}{}{}{}",
+            label(&return_label(name)),
             mov(
                 Operand::Register(Register::R6),
                 Operand::Register(Register::R5)
@@ -212,18 +866,18 @@ pub mod myarchitecture {
         )
     }
 
-    /// mov rsp(r9), rbp(r8)
-    /// pop rbp(r8)
+    /// .L.return.name:
+    /// mov sp, x29
+    /// ldp x29, lr, [sp], #16
     /// ret
     #[cfg(target_arch = "aarch64")]
-    pub fn program_epilogue() -> String {
+    pub fn function_epilogue(name: &str) -> String {
         format!(
-            "{}{}{}",
-            mov(
-                Operand::Register(Register::R9),
-                Operand::Register(Register::R8)
-            ),
-            pop(Operand::Register(Register::R8)),
+            "{}\tmov {}, x29\n\tldp x29, {}, [{}], #16\n{}",
+            label(&return_label(name)),
+            Register::R13,
+            Register::R14,
+            Register::R13,
             ret()
         )
     }
@@ -260,71 +914,123 @@ pub mod myarchitecture {
         )
     }
 
+    pub fn gen_global(name: &str) -> String {
+        gen_label_address(&symbol_name(name))
+    }
+
+    pub fn gen_string(id: usize) -> String {
+        gen_label_address(&string_label(id))
+    }
+
+    /// lea rax, [rip + symbol]
+    /// push rax
+    /// 位置独立なコードになるようripからの相対で求める
+    #[cfg(target_arch = "x86_64")]
+    fn gen_label_address(symbol: &str) -> String {
+        format!(
+            "\tlea {}, [rip + {}]\n{}",
+            Register::R0,
+            symbol,
+            push(Operand::Register(Register::R0))
+        )
+    }
+
+    /// adrp x0, symbol@PAGE
+    /// add x0, x0, symbol@PAGEOFF
+    /// push x0
+    #[cfg(target_arch = "aarch64")]
+    fn gen_label_address(symbol: &str) -> String {
+        format!(
+            "\tadrp {}, {}@PAGE\n\tadd {}, {}, {}@PAGEOFF\n{}",
+            Register::R0,
+            symbol,
+            Register::R0,
+            Register::R0,
+            symbol,
+            push(Operand::Register(Register::R0))
+        )
+    }
+
     /// pop r0
-    /// mov r0, [r0]
+    /// mov r0, [r0] (sizeバイト分)
     /// push r0
-    #[cfg(target_arch = "x86_64")]
-    pub fn pop_val() -> String {
+    pub fn load(size: usize, signed: bool) -> String {
         format!(
             "{}{}{}",
             pop(Operand::Register(Register::R0)),
-            mov(
-                Operand::Register(Register::R0),
-                Operand::Address(Register::R0)
-            ),
+            load_sized(Register::R0, Register::R0, size, signed),
             push(Operand::Register(Register::R0))
         )
     }
 
     /// pop r0
-    /// mov r0, [r0]
+    /// (r0の下位sizeバイトを広げる)
     /// push r0
-    #[cfg(target_arch = "aarch64")]
-    pub fn pop_val() -> String {
+    /// 計算結果や代入した値を型の大きさに切り詰める
+    pub fn extend(size: usize, signed: bool) -> String {
         format!(
             "{}{}{}",
             pop(Operand::Register(Register::R0)),
-            ldr(
-                Operand::Register(Register::R0),
-                Operand::Address(Register::R0)
-            ),
+            extend_sized(Register::R0, size, signed),
             push(Operand::Register(Register::R0))
         )
     }
 
     /// pop r1
     /// pop r0
-    /// mov [r0], r1
+    /// mov [r0], r1 (sizeバイト分)
     /// push r1
-    #[cfg(target_arch = "x86_64")]
-    pub fn pop_lvar() -> String {
+    pub fn store(size: usize) -> String {
         format!(
             "{}{}{}{}",
             pop(Operand::Register(Register::R1)),
             pop(Operand::Register(Register::R0)),
-            mov(
-                Operand::Address(Register::R0),
-                Operand::Register(Register::R1)
-            ),
+            store_sized(Register::R0, Register::R1, size),
             push(Operand::Register(Register::R1))
         )
     }
 
     /// pop r1
     /// pop r0
-    /// mov [r0], r1
-    /// push r1
-    #[cfg(target_arch = "aarch64")]
-    pub fn pop_lvar() -> String {
-        format!(
-            "{}{}{}{}",
+    /// mov r3, [r1] ...
+    /// mov [r2], r3 ...
+    /// push r0
+    /// 右辺のアドレスr1から左辺のアドレスr0へsizeバイトを写す
+    /// 8, 4, 2, 1バイトずつ読み書きし、左辺のアドレスを結果として残す
+    pub fn copy_struct(size: usize) -> String {
+        let mut asm = format!(
+            "{}{}{}",
             pop(Operand::Register(Register::R1)),
             pop(Operand::Register(Register::R0)),
-            str(
-                Operand::Register(Register::R1),
-                Operand::Address(Register::R0)
-            ),
-            push(Operand::Register(Register::R1))
+            mov(
+                Operand::Register(Register::R2),
+                Operand::Register(Register::R0)
+            )
+        );
+        let mut rest = size;
+        for chunk in [8, 4, 2, 1] {
+            while rest >= chunk {
+                asm.push_str(&load_sized(Register::R3, Register::R1, chunk, false));
+                asm.push_str(&store_sized(Register::R2, Register::R3, chunk));
+                asm.push_str(&add(Operand::Register(Register::R1), Operand::Num(chunk)));
+                asm.push_str(&add(Operand::Register(Register::R2), Operand::Num(chunk)));
+                rest -= chunk;
+            }
+        }
+        asm.push_str(&push(Operand::Register(Register::R0)));
+        asm
+    }
+
+    /// pop r0
+    /// push r0
+    /// push r0
+    /// スタックの先頭の値を複製する
+    pub fn dup() -> String {
+        format!(
+            "{}{}{}",
+            pop(Operand::Register(Register::R0)),
+            push(Operand::Register(Register::R0)),
+            push(Operand::Register(Register::R0))
         )
     }
 
@@ -344,37 +1050,71 @@ pub mod myarchitecture {
         format!("\tpush {}\n", rd)
     }
 
-    /// sub r9, #8
-    /// mov r0, rd
-    /// str r0, r9
+    /// mov x1, rd
+    /// str x1, [sp, #-16]!
+    /// spは16の倍数に保つ必要があるため、一つの値に16バイト使う
     #[cfg(target_arch = "aarch64")]
     pub fn push(rd: Operand) -> String {
         format!(
-            "{}{}{}",
-            sub(Operand::Register(Register::R9), Operand::Num(8)),
+            "{}\tstr {}, [{}, #-16]!\n",
             mov(Operand::Register(Register::R1), rd),
-            str(
-                Operand::Register(Register::R1),
-                Operand::Address(Register::R9)
+            Register::R1,
+            Register::R13
+        )
+    }
+
+    /// pushの即値は32ビットまでなので、超える場合はraxを経由する
+    #[cfg(target_arch = "x86_64")]
+    pub fn push_num(n: NumType) -> String {
+        if n <= i32::MAX as NumType {
+            push(Operand::Num(n))
+        } else {
+            format!(
+                "{}{}",
+                mov(Operand::Register(Register::R0), Operand::Num(n)),
+                push(Operand::Register(Register::R0))
             )
+        }
+    }
+
+    /// movz x1, #n
+    /// movk x1, #n, lsl #16 ...
+    /// str x1, [sp, #-16]!
+    #[cfg(target_arch = "aarch64")]
+    pub fn push_num(n: NumType) -> String {
+        format!(
+            "{}\tstr {}, [{}, #-16]!\n",
+            mov_imm(Register::R1, n),
+            Register::R1,
+            Register::R13
         )
     }
 
+    /// movz rd, #n
+    /// movk rd, #n, lsl #16 ...
+    /// movの即値に収まらない値は16ビットずつ組み立てる
+    #[cfg(target_arch = "aarch64")]
+    fn mov_imm(rd: Register, n: NumType) -> String {
+        let mut asm = format!("\tmovz {}, #{}\n", rd, n & 0xffff);
+        for shift in [16, 32, 48] {
+            let part = (n >> shift) & 0xffff;
+            if part != 0 {
+                asm.push_str(&format!("\tmovk {}, #{}, lsl #{}\n", rd, part, shift));
+            }
+        }
+        asm
+    }
+
     /// pop rd
     #[cfg(target_arch = "x86_64")]
     fn pop(rd: Operand) -> String {
         format!("\tpop {}\n", rd)
     }
 
-    /// ldr rd, r9
-    /// add r9, #8
+    /// ldr rd, [sp], #16
     #[cfg(target_arch = "aarch64")]
-    pub fn pop(rd: Operand) -> String {
-        format!(
-            "{}{}",
-            ldr(rd, Operand::Address(Register::R9)),
-            add(Operand::Register(Register::R9), Operand::Num(8))
-        )
+    fn pop(rd: Operand) -> String {
+        format!("\tldr {}, [{}], #16\n", rd, Register::R13)
     }
 
     pub fn add_arg() -> String {
@@ -391,7 +1131,21 @@ pub mod myarchitecture {
 
     #[cfg(target_arch = "aarch64")]
     fn add(rd: Operand, rn: Operand) -> String {
-        format!("\tadd {}, {}, {}\n", rd, rd, rn) // rd <- rn + src2
+        let (setup, rn) = arith_operand(rn);
+        format!("{}\tadd {}, {}, {}\n", setup, rd, rd, rn) // rd <- rn + src2
+    }
+
+    /// movz x9, #n ...
+    /// add, subの即値は12ビットまでなので、収まらない値はx9に入れてから使う
+    /// 大きな配列を置いたフレームの確保や変数のアドレス計算で必要になる
+    #[cfg(target_arch = "aarch64")]
+    fn arith_operand(rn: Operand) -> (String, Operand) {
+        match rn {
+            Operand::Num(n) if n > 4095 => {
+                (mov_imm(Register::R9, n), Operand::Register(Register::R9))
+            }
+            rn => (String::new(), rn),
+        }
     }
 
     pub fn sub_arg() -> String {
@@ -408,7 +1162,8 @@ pub mod myarchitecture {
 
     #[cfg(target_arch = "aarch64")]
     fn sub(rd: Operand, rn: Operand) -> String {
-        format!("\tsub {}, {}, {}\n", rd, rd, rn) // rd <- rn - src2
+        let (setup, rn) = arith_operand(rn);
+        format!("{}\tsub {}, {}, {}\n", setup, rd, rd, rn) // rd <- rn - src2
     }
 
     pub fn mul_arg() -> String {
@@ -428,21 +1183,178 @@ pub mod myarchitecture {
         format!("\tmul {}, {}, {}\n", rd, rd, rn) // rd <- rn x rm
     }
 
-    pub fn div_arg() -> String {
+    pub fn div_arg(signed: bool) -> String {
         div(
             Operand::Register(Register::R0),
             Operand::Register(Register::R1),
+            signed,
+        )
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn div(_: Operand, rn: Operand, signed: bool) -> String {
+        if signed {
+            format!("\tcqo\n\tidiv {}\n", rn)
+        } else {
+            format!("\txor edx, edx\n\tdiv {}\n", rn)
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn div(rd: Operand, rn: Operand, signed: bool) -> String {
+        let op = if signed { "sdiv" } else { "udiv" };
+        format!("\t{} {}, {}, {}\n", op, rd, rd, rn)
+    }
+
+    pub fn mod_arg(signed: bool) -> String {
+        modulo(
+            Operand::Register(Register::R0),
+            Operand::Register(Register::R1),
+            signed,
+        )
+    }
+
+    /// 余りはrdxに残る
+    #[cfg(target_arch = "x86_64")]
+    fn modulo(rd: Operand, rn: Operand, signed: bool) -> String {
+        format!(
+            "{}{}",
+            div(rd.clone(), rn, signed),
+            mov(rd, Operand::Register(Register::R3))
+        )
+    }
+
+    /// x2 <- rd / rn
+    /// rd <- rd - x2 * rn
+    #[cfg(target_arch = "aarch64")]
+    fn modulo(rd: Operand, rn: Operand, signed: bool) -> String {
+        let quotient = Operand::Register(Register::R2);
+        let op = if signed { "sdiv" } else { "udiv" };
+        format!(
+            "\t{} {}, {}, {}\n\tmsub {}, {}, {}, {}\n",
+            op, quotient, rd, rn, rd, quotient, rn, rd
+        )
+    }
+
+    pub fn bit_and_arg() -> String {
+        bit_and(
+            Operand::Register(Register::R0),
+            Operand::Register(Register::R1),
+        )
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn bit_and(rd: Operand, rn: Operand) -> String {
+        format!("\tand {}, {}\n", rd, rn)
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn bit_and(rd: Operand, rn: Operand) -> String {
+        format!("\tand {}, {}, {}\n", rd, rd, rn)
+    }
+
+    pub fn bit_or_arg() -> String {
+        bit_or(
+            Operand::Register(Register::R0),
+            Operand::Register(Register::R1),
+        )
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn bit_or(rd: Operand, rn: Operand) -> String {
+        format!("\tor {}, {}\n", rd, rn)
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn bit_or(rd: Operand, rn: Operand) -> String {
+        format!("\torr {}, {}, {}\n", rd, rd, rn)
+    }
+
+    pub fn bit_xor_arg() -> String {
+        bit_xor(
+            Operand::Register(Register::R0),
+            Operand::Register(Register::R1),
+        )
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn bit_xor(rd: Operand, rn: Operand) -> String {
+        format!("\txor {}, {}\n", rd, rn)
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn bit_xor(rd: Operand, rn: Operand) -> String {
+        format!("\teor {}, {}, {}\n", rd, rd, rn)
+    }
+
+    /// pop r0
+    /// r0 <- ~r0
+    /// push r0
+    pub fn bit_not() -> String {
+        format!(
+            "{}{}{}",
+            pop(Operand::Register(Register::R0)),
+            not(Operand::Register(Register::R0)),
+            push(Operand::Register(Register::R0))
+        )
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn not(rd: Operand) -> String {
+        format!("\tnot {}\n", rd)
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn not(rd: Operand) -> String {
+        format!("\tmvn {}, {}\n", rd, rd)
+    }
+
+    pub fn shl_arg() -> String {
+        shl(
+            Operand::Register(Register::R0),
+            Operand::Register(Register::R1),
         )
     }
 
+    /// シフト量はclで渡す
     #[cfg(target_arch = "x86_64")]
-    fn div(_: Operand, rn: Operand) -> String {
-        format!("\tcqo\n\tidiv {}\n", rn)
+    fn shl(rd: Operand, rn: Operand) -> String {
+        format!(
+            "{}\tshl {}, cl\n",
+            mov(Operand::Register(Register::R4), rn),
+            rd
+        )
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn shl(rd: Operand, rn: Operand) -> String {
+        format!("\tlsl {}, {}, {}\n", rd, rd, rn)
+    }
+
+    // 符号付きは算術シフト、符号なしは論理シフト
+    pub fn shr_arg(signed: bool) -> String {
+        shr(
+            Operand::Register(Register::R0),
+            Operand::Register(Register::R1),
+            signed,
+        )
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn shr(rd: Operand, rn: Operand, signed: bool) -> String {
+        let op = if signed { "sar" } else { "shr" };
+        format!(
+            "{}\t{} {}, cl\n",
+            mov(Operand::Register(Register::R4), rn),
+            op,
+            rd
+        )
     }
 
     #[cfg(target_arch = "aarch64")]
-    fn div(rd: Operand, rn: Operand) -> String {
-        format!("\tudiv {}, {}, {}\n", rd, rd, rn)
+    fn shr(rd: Operand, rn: Operand, signed: bool) -> String {
+        let op = if signed { "asr" } else { "lsr" };
+        format!("\t{} {}, {}, {}\n", op, rd, rd, rn)
     }
 
     #[cfg(target_arch = "x86_64")]
@@ -489,38 +1401,45 @@ pub mod myarchitecture {
         format!("\tcmp {}, {}\n\tcset {}, NE\n", rd, rn, rd)
     }
 
-    pub fn less_arg() -> String {
+    // 符号なしの比較は下(below)・以下を使う
+    pub fn less_arg(signed: bool) -> String {
         less(
             Operand::Register(Register::R0),
             Operand::Register(Register::R1),
+            signed,
         )
     }
 
     #[cfg(target_arch = "x86_64")]
-    fn less(rd: Operand, rn: Operand) -> String {
-        format!("\tcmp {}, {}\n\tsetl al\n\tmovzb {}, al\n", rd, rn, rd)
+    fn less(rd: Operand, rn: Operand, signed: bool) -> String {
+        let op = if signed { "setl" } else { "setb" };
+        format!("\tcmp {}, {}\n\t{} al\n\tmovzb {}, al\n", rd, rn, op, rd)
     }
 
     #[cfg(target_arch = "aarch64")]
-    fn less(rd: Operand, rn: Operand) -> String {
-        format!("\tcmp {}, {}\n\tcset {}, LT\n", rd, rn, rd)
+    fn less(rd: Operand, rn: Operand, signed: bool) -> String {
+        let cond = if signed { "LT" } else { "LO" };
+        format!("\tcmp {}, {}\n\tcset {}, {}\n", rd, rn, rd, cond)
     }
 
-    pub fn less_or_eq_arg() -> String {
+    pub fn less_or_eq_arg(signed: bool) -> String {
         less_or_eq(
             Operand::Register(Register::R0),
             Operand::Register(Register::R1),
+            signed,
         )
     }
 
     #[cfg(target_arch = "x86_64")]
-    fn less_or_eq(rd: Operand, rn: Operand) -> String {
-        format!("\tcmp {}, {}\n\tsetle al\n\tmovzb {}, al\n", rd, rn, rd)
+    fn less_or_eq(rd: Operand, rn: Operand, signed: bool) -> String {
+        let op = if signed { "setle" } else { "setbe" };
+        format!("\tcmp {}, {}\n\t{} al\n\tmovzb {}, al\n", rd, rn, op, rd)
     }
 
     #[cfg(target_arch = "aarch64")]
-    fn less_or_eq(rd: Operand, rn: Operand) -> String {
-        format!("\tcmp {}, {}\n\tcset {}, LS\n", rd, rn, rd)
+    fn less_or_eq(rd: Operand, rn: Operand, signed: bool) -> String {
+        let cond = if signed { "LE" } else { "LS" };
+        format!("\tcmp {}, {}\n\tcset {}, {}\n", rd, rn, rd, cond)
     }
 
     fn ret() -> String {
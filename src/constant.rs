@@ -0,0 +1,144 @@
+pub mod myconstant {
+    use crate::{error::myerror::MyError, tree::mytree::*, types::mytype::Type};
+
+    fn not_constant() -> MyError {
+        MyError {
+            message: "expression is not a compile-time constant".to_string(),
+        }
+    }
+
+    // 計算結果を型の大きさに切り詰め、符号に合わせて広げる
+    pub fn normalize(value: i64, ty: &Type) -> i64 {
+        match (ty.size(), ty.is_unsigned()) {
+            (1, false) => value as i8 as i64,
+            (1, true) => value as u8 as i64,
+            (2, false) => value as i16 as i64,
+            (2, true) => value as u16 as i64,
+            (4, false) => value as i32 as i64,
+            (4, true) => value as u32 as i64,
+            _ => value,
+        }
+    }
+
+    // 定数式をコンパイル時に計算する
+    // 配列の要素数やcaseの値など、値が確定していなければならない場所で使う
+    pub fn eval(tree: &Tree) -> Result<i64, MyError> {
+        match tree {
+            Tree::Num(n, _) => Ok(*n as i64),
+            Tree::Node(NodeKind::LogicalAnd, lhs, rhs, _) => {
+                Ok((eval(lhs)? != 0 && eval(rhs)? != 0) as i64)
+            }
+            Tree::Node(NodeKind::LogicalOr, lhs, rhs, _) => {
+                Ok((eval(lhs)? != 0 || eval(rhs)? != 0) as i64)
+            }
+            Tree::Cond(cond, then, els, ty) => {
+                let value = if eval(cond)? != 0 {
+                    eval(then)?
+                } else {
+                    eval(els)?
+                };
+                Ok(normalize(value, ty))
+            }
+            Tree::Cast(tree, ty) => Ok(normalize(eval(tree)?, ty)),
+            Tree::Unary(NodeKind::Not, tree, _) => Ok((eval(tree)? == 0) as i64),
+            Tree::Unary(NodeKind::BitNot, tree, ty) => Ok(normalize(!eval(tree)?, ty)),
+            Tree::Node(kind, lhs, rhs, ty) => {
+                // 比較は両辺を揃えた型の、それ以外は結果の型の符号で計算する
+                let unsigned = match kind {
+                    NodeKind::Less | NodeKind::LessOrEqual => {
+                        Type::common_type(lhs.ty(), rhs.ty()).is_unsigned()
+                    }
+                    _ => ty.is_unsigned(),
+                };
+                // 両辺を揃えた型に変換してから計算する
+                // シフトは左辺の型で計算し、右辺はそのまま使う
+                let (lhs, rhs) = match kind {
+                    NodeKind::Shl | NodeKind::Shr => (normalize(eval(lhs)?, ty), eval(rhs)?),
+                    _ => {
+                        let common = Type::common_type(lhs.ty(), rhs.ty());
+                        (
+                            normalize(eval(lhs)?, &common),
+                            normalize(eval(rhs)?, &common),
+                        )
+                    }
+                };
+                let value = match kind {
+                    NodeKind::Add => lhs.wrapping_add(rhs),
+                    NodeKind::Sub => lhs.wrapping_sub(rhs),
+                    NodeKind::Mul => lhs.wrapping_mul(rhs),
+                    NodeKind::Div | NodeKind::Mod if rhs == 0 => {
+                        return Err(MyError {
+                            message: "division by zero in constant expression".to_string(),
+                        });
+                    }
+                    NodeKind::Div if unsigned => ((lhs as u64) / (rhs as u64)) as i64,
+                    NodeKind::Div => lhs.wrapping_div(rhs),
+                    NodeKind::Mod if unsigned => ((lhs as u64) % (rhs as u64)) as i64,
+                    NodeKind::Mod => lhs.wrapping_rem(rhs),
+                    NodeKind::BitAnd => lhs & rhs,
+                    NodeKind::BitOr => lhs | rhs,
+                    NodeKind::BitXor => lhs ^ rhs,
+                    NodeKind::Shl => lhs.wrapping_shl(rhs as u32),
+                    NodeKind::Shr if unsigned => (lhs as u64).wrapping_shr(rhs as u32) as i64,
+                    NodeKind::Shr => lhs.wrapping_shr(rhs as u32),
+                    NodeKind::Equality => (lhs == rhs) as i64,
+                    NodeKind::Nonequality => (lhs != rhs) as i64,
+                    NodeKind::Less if unsigned => ((lhs as u64) < (rhs as u64)) as i64,
+                    NodeKind::Less => (lhs < rhs) as i64,
+                    NodeKind::LessOrEqual if unsigned => ((lhs as u64) <= (rhs as u64)) as i64,
+                    NodeKind::LessOrEqual => (lhs <= rhs) as i64,
+                    _ => return Err(not_constant()),
+                };
+                Ok(normalize(value, ty))
+            }
+            _ => Err(not_constant()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        constant::myconstant::eval, lexer::mylexer::Lexer, parser::myparser::*, tree::mytree::*,
+    };
+
+    // int main() { expr; } の式を計算する
+    fn eval_body(input: &str) -> Option<i64> {
+        let lexer = &mut Lexer::new(&format!("int main() {{ {} }}", input));
+        let mut functions = program(lexer).functions;
+        match functions.remove(0).body {
+            Tree::Block(mut trees) => eval(&trees.pop()?).ok(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_eval() {
+        assert_eq!(eval_body("1 + 2 * 3;"), Some(7));
+        assert_eq!(eval_body("(10 - 4) / 3 == 2;"), Some(1));
+        assert_eq!(eval_body("3 - 5;"), Some(-2));
+        assert_eq!(eval_body("1 < 2 + (2 >= 3);"), Some(1));
+        assert_eq!(eval_body("sizeof(long) * 2;"), Some(16));
+        assert_eq!(eval_body("sizeof(int) - 5 > 0;"), Some(1));
+        assert_eq!(eval_body("int a; a + 1;"), None);
+        assert_eq!(eval_body("1 / 0;"), None);
+        assert_eq!(eval_body("7 % 3 + (6 & 3) + (4 | 1) + (5 ^ 1);"), Some(12));
+        assert_eq!(eval_body("1 << 4 >> 2;"), Some(4));
+        assert_eq!(eval_body("-8 >> 1;"), Some(-4));
+        assert_eq!(eval_body("~0 == -1;"), Some(1));
+        assert_eq!(eval_body("-1 / 2;"), Some(0));
+        assert_eq!(eval_body("2147483647 + 1;"), Some(-2147483648));
+        assert_eq!(eval_body("1 ? 2 : 1 / 0;"), Some(2));
+        assert_eq!(eval_body("0 ? 1 : 2 ? 3 : 4;"), Some(3));
+        assert_eq!(eval_body("(1, 2);"), None);
+        assert_eq!(eval_body("(char)300;"), Some(44));
+        assert_eq!(eval_body("(unsigned char)-1 + (short)65535;"), Some(254));
+        assert_eq!(eval_body("-2 < 4294967295u;"), Some(1));
+        assert_eq!(eval_body("-1 == 4294967295u;"), Some(1));
+        assert_eq!(eval_body("-4 / 2u == 2147483646;"), Some(1));
+        assert_eq!(
+            eval_body("int a[-2 < 4294967295u ? 1 : 2]; sizeof(a);"),
+            Some(4)
+        );
+    }
+}
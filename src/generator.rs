@@ -1,58 +1,631 @@
 pub mod mygenerator {
-    use std::process::exit;
+    use crate::{
+        architecture::myarchitecture::*, error::myerror::MyError, symbol::mysymbol::Storage,
+        tree::mytree::*, types::mytype::Type,
+    };
 
-    use crate::{architecture::myarchitecture::*, tree::mytree::*};
+    // 生成中の関数の情報
+    pub struct Context {
+        func_name: String,
+        label_count: usize,
+        // 内側のループほど末尾に積まれる
+        break_labels: Vec<String>,
+        continue_labels: Vec<String>,
+        // 内側のswitch文ほど末尾に積まれるラベルの番号
+        switch_labels: Vec<usize>,
+    }
+
+    impl Default for Context {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Context {
+        pub fn new() -> Context {
+            Context {
+                func_name: String::new(),
+                label_count: 0,
+                break_labels: vec![],
+                continue_labels: vec![],
+                switch_labels: vec![],
+            }
+        }
+
+        // 一番内側のswitch文のラベルの番号
+        // switch文の外のcase, defaultはパーサーが弾くので、ここに来れば内部エラー
+        fn current_switch(&self) -> Result<usize, MyError> {
+            self.switch_labels.last().copied().ok_or_else(|| MyError {
+                message: "internal error: case or default label outside of switch".to_string(),
+            })
+        }
+
+        // ラベルが重複しないよう通し番号を払い出す
+        fn new_label_number(&mut self) -> usize {
+            self.label_count += 1;
+            self.label_count
+        }
+    }
+
+    // 左辺値のアドレスをスタックに積む
+    fn generate_address(
+        assembly: &mut String,
+        context: &mut Context,
+        tree: Tree,
+    ) -> Result<(), MyError> {
+        match tree {
+            Tree::Val(var) => {
+                match var.storage {
+                    Storage::Local => assembly.push_str(&gen_val(var.offset)),
+                    Storage::Global => assembly.push_str(&gen_global(&var.name)),
+                }
+                Ok(())
+            }
+            Tree::Str(id, _) => {
+                assembly.push_str(&gen_string(id));
+                Ok(())
+            }
+            // *pのアドレスはpの値そのもの
+            Tree::Unary(NodeKind::Deref, tree, _) => generate_assembly(assembly, context, *tree),
+            // 構造体の先頭のアドレスにメンバーのオフセットを足す
+            Tree::Member(tree, member) => {
+                generate_address(assembly, context, *tree)?;
+                if member.offset > 0 {
+                    assembly.push_str(&push_num(member.offset));
+                    generate_binary_op(assembly, NodeKind::Add, true)?;
+                }
+                Ok(())
+            }
+            _ => Err(MyError {
+                message: "expression is not an lvalue".to_string(),
+            }),
+        }
+    }
 
-    fn generate_val(assembly: &mut String, offset: usize) {
-        assembly.push_str(&gen_val(offset));
+    // グローバル変数の領域を確保する
+    // 初期値のあるものは.dataに、ないものは.bssに置く
+    pub fn generate_global(assembly: &mut String, global: GlobalVar) {
+        let ty = &global.var.ty;
+        match global.init {
+            Some(value) => {
+                assembly.push_str(&section(Section::Data));
+                assembly.push_str(&data_label(&global.var.name, ty.align()));
+                assembly.push_str(&data_value(value, ty.size()));
+            }
+            None => {
+                assembly.push_str(&section(Section::Bss));
+                assembly.push_str(&data_label(&global.var.name, ty.align()));
+                assembly.push_str(&data_zero(ty.size()));
+            }
+        }
     }
 
-    // 構文木をアセンブリに変換する
-    pub fn generate_assembly(assembly: &mut String, tree: Tree) {
-        if let Tree::Num(n) = tree {
-            assembly.push_str(&push(Operand::Num(n)));
+    // 文字列リテラルを読み取り専用の領域に置く
+    pub fn generate_strings(assembly: &mut String, strings: Vec<Vec<u8>>) {
+        if strings.is_empty() {
             return;
         }
+        assembly.push_str(&section(Section::Rodata));
+        for (id, bytes) in strings.iter().enumerate() {
+            assembly.push_str(&label(&string_label(id)));
+            assembly.push_str(&data_bytes(bytes));
+        }
+    }
+
+    // 8バイト未満の値を型に合わせて符号拡張またはゼロ拡張する
+    fn generate_extend(assembly: &mut String, ty: &Type) {
+        if ty.is_integer() && ty.size() < 8 {
+            assembly.push_str(&extend(ty.size(), !ty.is_unsigned()));
+        }
+    }
+
+    // スタックの上二つの値に二項演算を行い、結果を積む
+    fn generate_binary_op(
+        assembly: &mut String,
+        kind: NodeKind,
+        signed: bool,
+    ) -> Result<(), MyError> {
+        assembly.push_str(&pop_arg());
+        match kind {
+            NodeKind::Equality => assembly.push_str(&eq_arg()),
+            NodeKind::Nonequality => assembly.push_str(&neq_arg()),
+            NodeKind::Less => assembly.push_str(&less_arg(signed)),
+            NodeKind::LessOrEqual => assembly.push_str(&less_or_eq_arg(signed)),
+            NodeKind::Add => assembly.push_str(&add_arg()),
+            NodeKind::Sub => assembly.push_str(&sub_arg()),
+            NodeKind::Mul => assembly.push_str(&mul_arg()),
+            NodeKind::Div => assembly.push_str(&div_arg(signed)),
+            NodeKind::Mod => assembly.push_str(&mod_arg(signed)),
+            NodeKind::BitAnd => assembly.push_str(&bit_and_arg()),
+            NodeKind::BitOr => assembly.push_str(&bit_or_arg()),
+            NodeKind::BitXor => assembly.push_str(&bit_xor_arg()),
+            NodeKind::Shl => assembly.push_str(&shl_arg()),
+            NodeKind::Shr => assembly.push_str(&shr_arg(signed)),
+            _ => {
+                return Err(MyError {
+                    message: "unexpected node".to_string(),
+                })
+            }
+        }
+        assembly.push_str(&push(Operand::Register(Register::R0)));
+        Ok(())
+    }
+
+    // 関数呼び出しの引数をレジスタとスタックに置き、スタックに渡す引数の数を返す
+    // 関数ポインタを通した呼び出しでは、呼び出す先のアドレスも求めておく
+    fn generate_args(
+        assembly: &mut String,
+        context: &mut Context,
+        args: Vec<Tree>,
+        callee: Option<Tree>,
+    ) -> Result<usize, MyError> {
+        let stack_args = args.len().saturating_sub(PARAM_REGISTERS.len());
+        let register_args = args.len() - stack_args;
+        assembly.push_str(&align_call_stack(stack_args));
+
+        // 後ろの引数から積み、第一引数がスタックの先頭に来るようにする
+        for arg in args.into_iter().rev() {
+            generate_assembly(assembly, context, arg)?;
+        }
+        if let Some(callee) = callee {
+            generate_assembly(assembly, context, callee)?;
+            assembly.push_str(&pop_callee());
+        }
+        for reg in PARAM_REGISTERS.iter().take(register_args) {
+            assembly.push_str(&pop_param(*reg));
+        }
+        Ok(stack_args)
+    }
+
+    // 関数定義をアセンブリに変換する
+    pub fn generate_function(
+        assembly: &mut String,
+        context: &mut Context,
+        function: Function,
+    ) -> Result<(), MyError> {
+        context.func_name = function.name;
+        assembly.push_str(&function_prologue(&context.func_name, function.stack_size));
+
+        // 引数をフレームに退避する
+        // レジスタに収まらなかった分は呼び出し元のスタックから写す
+        for (i, var) in function.params.into_iter().enumerate() {
+            let size = var.ty.size();
+            match PARAM_REGISTERS.get(i) {
+                Some(reg) => assembly.push_str(&store_param(*reg, var.offset, size)),
+                None => assembly.push_str(&store_stack_param(
+                    i - PARAM_REGISTERS.len(),
+                    var.offset,
+                    size,
+                )),
+            }
+        }
 
-        if let Tree::Val(o) = tree {
-            generate_val(assembly, o);
-            assembly.push_str(&pop_val());
+        generate_stmt(assembly, context, function.body)?;
+
+        // returnがなければ最後の式の結果がraxに残り、返される
+        assembly.push_str(&function_epilogue(&context.func_name));
+        Ok(())
+    }
+
+    // ソースに書かれたラベル
+    // 通し番号で終わる内部のラベルと重ならないよう、関数名とラベル名をつなげる
+    fn user_label(func_name: &str, name: &str) -> String {
+        format!(".L.label.{}.{}", func_name, name)
+    }
+
+    fn case_label(n: usize, index: usize) -> String {
+        format!(".L.case.{}.{}", n, index)
+    }
+
+    // switch文の値に合うcaseへ飛ぶ
+    // caseの値が密に並んでいれば表を引き、疎らなら順に比較する
+    fn generate_dispatch(assembly: &mut String, n: usize, values: &[i64], default_label: &str) {
+        let min = values.iter().min().copied().unwrap_or(0);
+        let max = values.iter().max().copied().unwrap_or(0);
+        let span = max as i128 - min as i128 + 1;
+        if values.len() < 4 || span > values.len() as i128 * 3 {
+            for (index, value) in values.iter().enumerate() {
+                assembly.push_str(&jump_if_equal(*value, &case_label(n, index)));
+            }
+            assembly.push_str(&jmp(default_label));
             return;
         }
 
-        if let Tree::Node(kind, lhs, rhs) = tree {
-            if let NodeKind::Assign = kind {
-                if let Tree::Val(o) = *lhs {
-                    generate_val(assembly, o);
+        // 値のないところはdefaultへ飛ぶ
+        let len = span as usize;
+        let mut labels = vec![default_label.to_string(); len];
+        for (index, value) in values.iter().enumerate() {
+            labels[(*value as i128 - min as i128) as usize] = case_label(n, index);
+        }
+        let table = format!(".L.table.{}", n);
+        assembly.push_str(&jump_table(min, len, &table, default_label));
+        assembly.push_str(&jump_table_data(&table, &labels));
+    }
+
+    // ループ本体を生成する
+    // 本体の中のbreak, continueは渡したラベルへ飛ぶ
+    fn generate_loop_body(
+        assembly: &mut String,
+        context: &mut Context,
+        body: Tree,
+        break_label: &str,
+        continue_label: &str,
+    ) -> Result<(), MyError> {
+        context.break_labels.push(break_label.to_string());
+        context.continue_labels.push(continue_label.to_string());
+        let result = generate_stmt(assembly, context, body);
+        context.break_labels.pop();
+        context.continue_labels.pop();
+        result
+    }
+
+    // 文をアセンブリに変換する
+    // 文の前後でスタックの深さは変わらない
+    pub fn generate_stmt(
+        assembly: &mut String,
+        context: &mut Context,
+        tree: Tree,
+    ) -> Result<(), MyError> {
+        match tree {
+            Tree::Return(tree) => {
+                generate_assembly(assembly, context, *tree)?;
+                assembly.push_str(&return_stmt(&context.func_name));
+            }
+            Tree::If(cond, then, els) => {
+                let n = context.new_label_number();
+                let else_label = format!(".L.else.{}", n);
+                let end_label = format!(".L.end.{}", n);
+
+                // 条件式の結果はスタックから降ろしてから分岐する
+                generate_assembly(assembly, context, *cond)?;
+                assembly.push_str(&jump_if_zero(&else_label));
+                generate_stmt(assembly, context, *then)?;
+                assembly.push_str(&jmp(&end_label));
+                assembly.push_str(&label(&else_label));
+                if let Some(els) = els {
+                    generate_stmt(assembly, context, *els)?;
+                }
+                assembly.push_str(&label(&end_label));
+            }
+            Tree::While(cond, body) => {
+                let n = context.new_label_number();
+                let begin_label = format!(".L.begin.{}", n);
+                let break_label = format!(".L.break.{}", n);
+
+                assembly.push_str(&label(&begin_label));
+                generate_assembly(assembly, context, *cond)?;
+                assembly.push_str(&jump_if_zero(&break_label));
+                generate_loop_body(assembly, context, *body, &break_label, &begin_label)?;
+                assembly.push_str(&jmp(&begin_label));
+                assembly.push_str(&label(&break_label));
+            }
+            Tree::DoWhile(body, cond) => {
+                let n = context.new_label_number();
+                let begin_label = format!(".L.begin.{}", n);
+                let continue_label = format!(".L.continue.{}", n);
+                let break_label = format!(".L.break.{}", n);
+
+                assembly.push_str(&label(&begin_label));
+                generate_loop_body(assembly, context, *body, &break_label, &continue_label)?;
+                assembly.push_str(&label(&continue_label));
+                generate_assembly(assembly, context, *cond)?;
+                assembly.push_str(&jump_if_zero(&break_label));
+                assembly.push_str(&jmp(&begin_label));
+                assembly.push_str(&label(&break_label));
+            }
+            Tree::For(init, cond, step, body) => {
+                let n = context.new_label_number();
+                let begin_label = format!(".L.begin.{}", n);
+                let continue_label = format!(".L.continue.{}", n);
+                let break_label = format!(".L.break.{}", n);
+
+                if let Some(init) = init {
+                    generate_stmt(assembly, context, *init)?;
+                }
+                assembly.push_str(&label(&begin_label));
+                // 条件式を省略した場合は無限ループになる
+                if let Some(cond) = cond {
+                    generate_assembly(assembly, context, *cond)?;
+                    assembly.push_str(&jump_if_zero(&break_label));
+                }
+                generate_loop_body(assembly, context, *body, &break_label, &continue_label)?;
+                assembly.push_str(&label(&continue_label));
+                if let Some(step) = step {
+                    generate_stmt(assembly, context, *step)?;
+                }
+                assembly.push_str(&jmp(&begin_label));
+                assembly.push_str(&label(&break_label));
+            }
+            Tree::Switch(cond, body, cases) => {
+                let n = context.new_label_number();
+                let break_label = format!(".L.break.{}", n);
+                let default_label = if cases.has_default {
+                    format!(".L.default.{}", n)
                 } else {
-                    eprintln!("The left-hand side value of the assignment is not a variable");
-                    exit(1);
+                    break_label.clone()
+                };
+
+                generate_assembly(assembly, context, *cond)?;
+                assembly.push_str(&pop_switch_value());
+                generate_dispatch(assembly, n, &cases.values, &default_label);
+
+                // switch文の中のcontinueは外側のループへ飛ぶ
+                context.break_labels.push(break_label.clone());
+                context.switch_labels.push(n);
+                let result = generate_stmt(assembly, context, *body);
+                context.break_labels.pop();
+                context.switch_labels.pop();
+                result?;
+                assembly.push_str(&label(&break_label));
+            }
+            Tree::Case(index, tree) => {
+                let n = context.current_switch()?;
+                assembly.push_str(&label(&case_label(n, index)));
+                generate_stmt(assembly, context, *tree)?;
+            }
+            Tree::Default(tree) => {
+                let n = context.current_switch()?;
+                assembly.push_str(&label(&format!(".L.default.{}", n)));
+                generate_stmt(assembly, context, *tree)?;
+            }
+            Tree::Goto(name) => {
+                assembly.push_str(&jmp(&user_label(&context.func_name, &name)));
+            }
+            Tree::Label(name, tree) => {
+                assembly.push_str(&label(&user_label(&context.func_name, &name)));
+                generate_stmt(assembly, context, *tree)?;
+            }
+            Tree::Block(trees) => {
+                for tree in trees {
+                    generate_stmt(assembly, context, tree)?;
+                }
+            }
+            Tree::Break => match context.break_labels.last() {
+                Some(l) => assembly.push_str(&jmp(l)),
+                None => {
+                    return Err(MyError {
+                        message: "break statement not within loop".to_string(),
+                    })
                 }
-                generate_assembly(assembly, *rhs);
-                assembly.push_str(&pop_lvar());
-                return;
+            },
+            Tree::Continue => match context.continue_labels.last() {
+                Some(l) => assembly.push_str(&jmp(l)),
+                None => {
+                    return Err(MyError {
+                        message: "continue statement not within loop".to_string(),
+                    })
+                }
+            },
+            _ => {
+                // 式文の結果はraxに残る
+                generate_assembly(assembly, context, tree)?;
+                assembly.push_str(&stmt_epilogue());
             }
+        }
+        Ok(())
+    }
 
-            generate_assembly(assembly, *lhs);
-            generate_assembly(assembly, *rhs);
+    // 式をアセンブリに変換する
+    // 式の結果はスタックに積まれる
+    pub fn generate_assembly(
+        assembly: &mut String,
+        context: &mut Context,
+        tree: Tree,
+    ) -> Result<(), MyError> {
+        match tree {
+            Tree::Num(n, _) => {
+                assembly.push_str(&push_num(n));
+            }
+            Tree::Val(_)
+            | Tree::Str(_, _)
+            | Tree::Member(_, _)
+            | Tree::Unary(NodeKind::Deref, _, _) => {
+                // 配列、構造体、関数は値を読まず先頭のアドレスのまま使う
+                let ty = tree.ty().clone();
+                generate_address(assembly, context, tree)?;
+                if !ty.is_array() && !ty.is_struct() && !ty.is_func() {
+                    assembly.push_str(&load(ty.size(), !ty.is_unsigned()));
+                }
+            }
+            Tree::Unary(NodeKind::Addr, tree, _) => {
+                generate_address(assembly, context, *tree)?;
+            }
+            Tree::Call(name, args, ty) => {
+                let stack_args = generate_args(assembly, context, args, None)?;
+                assembly.push_str(&call(&name, stack_args));
+                // 戻り値の上位ビットは不定なので型に合わせる
+                generate_extend(assembly, &ty);
+            }
+            Tree::IndirectCall(callee, args, ty) => {
+                let stack_args = generate_args(assembly, context, args, Some(*callee))?;
+                assembly.push_str(&call_indirect(stack_args));
+                generate_extend(assembly, &ty);
+            }
+            // 値は8バイトに広げて持っているので、整数の型に合わせ直すだけでよい
+            Tree::Cast(tree, ty) => {
+                generate_assembly(assembly, context, *tree)?;
+                generate_extend(assembly, &ty);
+            }
+            Tree::Node(NodeKind::Assign, lhs, rhs, ty) => {
+                if ty.is_array() {
+                    return Err(MyError {
+                        message: "array type is not assignable".to_string(),
+                    });
+                }
+                generate_address(assembly, context, *lhs)?;
+                generate_assembly(assembly, context, *rhs)?;
+                // 構造体は右辺のアドレスから中身を写す
+                if ty.is_struct() {
+                    assembly.push_str(&copy_struct(ty.size()));
+                    return Ok(());
+                }
+                assembly.push_str(&store(ty.size()));
+                // 式の値は代入先の型に変換した値になる
+                generate_extend(assembly, &ty);
+            }
+            // 選ばれた側だけを評価する
+            Tree::Cond(cond, then, els, ty) => {
+                let n = context.new_label_number();
+                let else_label = format!(".L.else.{}", n);
+                let end_label = format!(".L.end.{}", n);
+
+                generate_assembly(assembly, context, *cond)?;
+                assembly.push_str(&jump_if_zero(&else_label));
+                generate_assembly(assembly, context, *then)?;
+                assembly.push_str(&jmp(&end_label));
+                assembly.push_str(&label(&else_label));
+                generate_assembly(assembly, context, *els)?;
+                assembly.push_str(&label(&end_label));
+                // 両辺を結果の型に揃える
+                generate_extend(assembly, &ty);
+            }
+            // 左辺の値は捨てて右辺の値を残す
+            Tree::Node(NodeKind::Comma, lhs, rhs, _) => {
+                generate_assembly(assembly, context, *lhs)?;
+                assembly.push_str(&stmt_epilogue());
+                generate_assembly(assembly, context, *rhs)?;
+            }
+            // 左辺で結果が決まれば右辺は評価しない
+            Tree::Node(NodeKind::LogicalAnd, lhs, rhs, _) => {
+                let n = context.new_label_number();
+                let false_label = format!(".L.false.{}", n);
+                let end_label = format!(".L.end.{}", n);
 
-            assembly.push_str(&pop_arg());
+                generate_assembly(assembly, context, *lhs)?;
+                assembly.push_str(&jump_if_zero(&false_label));
+                generate_assembly(assembly, context, *rhs)?;
+                assembly.push_str(&jump_if_zero(&false_label));
+                assembly.push_str(&push_num(1));
+                assembly.push_str(&jmp(&end_label));
+                assembly.push_str(&label(&false_label));
+                assembly.push_str(&push_num(0));
+                assembly.push_str(&label(&end_label));
+            }
+            Tree::Node(NodeKind::LogicalOr, lhs, rhs, _) => {
+                let n = context.new_label_number();
+                let true_label = format!(".L.true.{}", n);
+                let end_label = format!(".L.end.{}", n);
 
-            match kind {
-                NodeKind::Equality => assembly.push_str(&eq_arg()),
-                NodeKind::Nonequality => assembly.push_str(&neq_arg()),
-                NodeKind::Less => assembly.push_str(&less_arg()),
-                NodeKind::LessOrEqual => assembly.push_str(&less_or_eq_arg()),
-                NodeKind::Add => assembly.push_str(&add_arg()),
-                NodeKind::Sub => assembly.push_str(&sub_arg()),
-                NodeKind::Mul => assembly.push_str(&mul_arg()),
-                NodeKind::Div => assembly.push_str(&div_arg()),
-                _ => {
-                    eprintln!("unexpected node");
-                    exit(1);
+                generate_assembly(assembly, context, *lhs)?;
+                assembly.push_str(&jump_if_not_zero(&true_label));
+                generate_assembly(assembly, context, *rhs)?;
+                assembly.push_str(&jump_if_not_zero(&true_label));
+                assembly.push_str(&push_num(0));
+                assembly.push_str(&jmp(&end_label));
+                assembly.push_str(&label(&true_label));
+                assembly.push_str(&push_num(1));
+                assembly.push_str(&label(&end_label));
+            }
+            // !xは x == 0 と同じ
+            Tree::Unary(NodeKind::Not, tree, _) => {
+                generate_assembly(assembly, context, *tree)?;
+                assembly.push_str(&push_num(0));
+                assembly.push_str(&pop_arg());
+                assembly.push_str(&eq_arg());
+                assembly.push_str(&push(Operand::Register(Register::R0)));
+            }
+            Tree::Unary(NodeKind::BitNot, tree, ty) => {
+                generate_assembly(assembly, context, *tree)?;
+                assembly.push_str(&bit_not());
+                generate_extend(assembly, &ty);
+            }
+            Tree::Node(kind, lhs, rhs, ty) => {
+                // 比較は両辺を揃えた型の、それ以外は結果の型の符号で命令を選ぶ
+                let signed = match kind {
+                    NodeKind::Less | NodeKind::LessOrEqual => {
+                        !Type::common_type(lhs.ty(), rhs.ty()).is_unsigned()
+                    }
+                    _ => !ty.is_unsigned(),
+                };
+                // 両辺を揃えた型に変換してから計算する
+                // シフトは左辺の型で計算し、右辺はそのまま使う
+                let shift = matches!(kind, NodeKind::Shl | NodeKind::Shr);
+                let op_ty = if shift {
+                    ty.clone()
+                } else {
+                    Type::common_type(lhs.ty(), rhs.ty())
+                };
+                generate_assembly(assembly, context, *lhs)?;
+                generate_extend(assembly, &op_ty);
+                generate_assembly(assembly, context, *rhs)?;
+                if !shift {
+                    generate_extend(assembly, &op_ty);
                 }
+
+                generate_binary_op(assembly, kind, signed)?;
+                // intの演算は64ビットで行うので、結果を32ビットに切り詰める
+                generate_extend(assembly, &ty);
+            }
+            Tree::AssignOp(kind, lhs, rhs, ty) => {
+                // 演算は左辺と右辺を揃えた型で行う
+                let shift = matches!(kind, NodeKind::Shl | NodeKind::Shr);
+                let op_ty = if shift {
+                    lhs.ty().promote()
+                } else {
+                    Type::common_type(lhs.ty(), rhs.ty())
+                };
+                // アドレスを複製し、一方を読み出しに、もう一方を書き込みに使う
+                generate_address(assembly, context, *lhs)?;
+                assembly.push_str(&dup());
+                assembly.push_str(&load(ty.size(), !ty.is_unsigned()));
+                generate_extend(assembly, &op_ty);
+                generate_assembly(assembly, context, *rhs)?;
+                if !shift {
+                    generate_extend(assembly, &op_ty);
+                }
+                generate_binary_op(assembly, kind, !op_ty.is_unsigned())?;
+                assembly.push_str(&store(ty.size()));
+                generate_extend(assembly, &ty);
             }
-            assembly.push_str(&push(Operand::Register(Register::R0)));
+            _ => {
+                return Err(MyError {
+                    message: "statement is not an expression".to_string(),
+                })
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generator::mygenerator::{generate_global, generate_stmt, Context},
+        lexer::mylexer::Lexer,
+        parser::myparser::program,
+        tree::mytree::Tree,
+    };
+
+    #[test]
+    fn test_global_init() {
+        let lexer = &mut Lexer::new(
+            "char c = 300; unsigned char u = -1; short s = 65537; int i = 4294967295; int main() { return 0; }",
+        );
+        let mut assembly = String::new();
+        for global in program(lexer).globals {
+            generate_global(&mut assembly, global);
+        }
+        // 初期値は変数の型に収まる値になる
+        let values: Vec<&str> = assembly
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| {
+                [".byte", ".short", ".long"]
+                    .iter()
+                    .any(|d| line.starts_with(d))
+            })
+            .collect();
+        assert_eq!(values, [".byte 44", ".byte 255", ".short 1", ".long -1"]);
+    }
+
+    #[test]
+    fn test_case_outside_switch() {
+        for tree in [
+            Tree::Case(0, Box::new(Tree::Break)),
+            Tree::Default(Box::new(Tree::Break)),
+        ] {
+            let result = generate_stmt(&mut String::new(), &mut Context::new(), tree);
+            assert!(result.is_err_and(|e| e.message.starts_with("internal error")));
         }
     }
 }
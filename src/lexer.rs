@@ -1,10 +1,15 @@
 pub mod mylexer {
-    use crate::{numtype::mynumtype::NumType, token::mytoken::*};
+    use crate::{
+        numtype::mynumtype::NumType,
+        symbol::mysymbol::{SymbolTable, Var},
+        token::mytoken::*,
+        types::mytype::Type,
+    };
 
     pub struct Lexer {
         input: Vec<char>,
         position: usize,
-        idents: Vec<Ident>,
+        symbols: SymbolTable,
     }
 
     impl Lexer {
@@ -12,38 +17,39 @@ pub mod mylexer {
             Lexer {
                 input: input.chars().collect(),
                 position: 0,
-                idents: vec![],
+                symbols: SymbolTable::new(),
             }
         }
 
         pub fn next_token(&mut self) -> Result<Token, ErrorToken> {
-            // 空白スキップ
-            while self.current_char().is_whitespace() {
-                self.proceed_char(1);
-            }
+            // 空白とコメントをスキップ
+            self.skip_trivia();
 
             let curr = self.current_char();
 
-            // 数字をまとめる
+            // skip_triviaが読み飛ばせなかった /* は閉じられていない
+            if curr == '/' && self.peek_char(1) == '*' {
+                let (line, column) = self.location_of(self.position);
+                return Err(ErrorToken::UnterminatedComment(line, column));
+            }
+
             let token = if Self::is_number(&curr) {
-                let mut number = vec![curr];
-                while Self::is_number(&self.peek_char(1)) {
-                    self.proceed_char(1);
-                    number.push(self.current_char());
-                }
-                let s: String = number.iter().collect();
-                Ok(Token::Operator(OperatorKind::Operand(
-                    s.parse::<NumType>().unwrap(),
-                )))
+                self.read_number()
+            } else if curr == '"' {
+                self.read_string()
+            } else if curr == '\'' {
+                self.read_char()
             } else if Self::is_ident_char(&curr) {
                 let mut ident = vec![curr];
-                while Self::is_ident_char(&self.peek_char(1)) {
+                while Self::is_ident_tail_char(&self.peek_char(1)) {
                     self.proceed_char(1);
                     ident.push(self.current_char());
                 }
-                Ok(Token::Operator(OperatorKind::Ident(Ident::new(
-                    &ident.iter().collect::<String>(),
-                ))))
+                let name = ident.iter().collect::<String>();
+                match KeywordKind::from_name(&name) {
+                    Some(k) => Ok(Token::Keyword(k)),
+                    None => Ok(Token::Operator(OperatorKind::Ident(Ident::new(&name)))),
+                }
             } else {
                 // 演算子を変換
                 match curr {
@@ -56,23 +62,105 @@ pub mod mylexer {
                         self.proceed_char(1);
                         Ok(Token::Operator(OperatorKind::Nonequality))
                     }
+                    '!' => Ok(Token::Operator(OperatorKind::Not)),
+                    '<' if (self.peek_char(1) == '<' && self.peek_char(2) == '=') => {
+                        self.proceed_char(2);
+                        Ok(Token::Operator(OperatorKind::ShlAssign))
+                    }
+                    '<' if (self.peek_char(1) == '<') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::Shl))
+                    }
                     '<' if (self.peek_char(1) == '=') => {
                         self.proceed_char(1);
                         Ok(Token::Operator(OperatorKind::LessOrEqual))
                     }
                     '<' => Ok(Token::Operator(OperatorKind::Less)),
+                    '>' if (self.peek_char(1) == '>' && self.peek_char(2) == '=') => {
+                        self.proceed_char(2);
+                        Ok(Token::Operator(OperatorKind::ShrAssign))
+                    }
+                    '>' if (self.peek_char(1) == '>') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::Shr))
+                    }
                     '>' if (self.peek_char(1) == '=') => {
                         self.proceed_char(1);
                         Ok(Token::Operator(OperatorKind::GreaterOrEqual))
                     }
                     '>' => Ok(Token::Operator(OperatorKind::Greater)),
+                    '+' if (self.peek_char(1) == '+') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::Inc))
+                    }
+                    '+' if (self.peek_char(1) == '=') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::AddAssign))
+                    }
                     '+' => Ok(Token::Operator(OperatorKind::Add)),
+                    '-' if (self.peek_char(1) == '-') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::Dec))
+                    }
+                    '-' if (self.peek_char(1) == '=') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::SubAssign))
+                    }
+                    '-' if (self.peek_char(1) == '>') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::Arrow))
+                    }
                     '-' => Ok(Token::Operator(OperatorKind::Sub)),
+                    '*' if (self.peek_char(1) == '=') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::MulAssign))
+                    }
                     '*' => Ok(Token::Operator(OperatorKind::Mul)),
+                    '/' if (self.peek_char(1) == '=') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::DivAssign))
+                    }
                     '/' => Ok(Token::Operator(OperatorKind::Div)),
+                    '%' if (self.peek_char(1) == '=') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::ModAssign))
+                    }
+                    '%' => Ok(Token::Operator(OperatorKind::Percent)),
+                    '^' if (self.peek_char(1) == '=') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::XorAssign))
+                    }
+                    '^' => Ok(Token::Operator(OperatorKind::Caret)),
+                    '~' => Ok(Token::Operator(OperatorKind::Tilde)),
+                    '&' if (self.peek_char(1) == '&') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::LogicalAnd))
+                    }
+                    '&' if (self.peek_char(1) == '=') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::AndAssign))
+                    }
+                    '&' => Ok(Token::Operator(OperatorKind::Ampersand)),
+                    '|' if (self.peek_char(1) == '|') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::LogicalOr))
+                    }
+                    '|' if (self.peek_char(1) == '=') => {
+                        self.proceed_char(1);
+                        Ok(Token::Operator(OperatorKind::OrAssign))
+                    }
+                    '|' => Ok(Token::Operator(OperatorKind::Pipe)),
                     '(' => Ok(Token::Operator(OperatorKind::LParen)),
                     ')' => Ok(Token::Operator(OperatorKind::RParen)),
+                    '{' => Ok(Token::Operator(OperatorKind::LBrace)),
+                    '}' => Ok(Token::Operator(OperatorKind::RBrace)),
+                    '[' => Ok(Token::Operator(OperatorKind::LBracket)),
+                    ']' => Ok(Token::Operator(OperatorKind::RBracket)),
                     ';' => Ok(Token::Operator(OperatorKind::Semi)),
+                    ',' => Ok(Token::Operator(OperatorKind::Comma)),
+                    '?' => Ok(Token::Operator(OperatorKind::Question)),
+                    '.' => Ok(Token::Operator(OperatorKind::Dot)),
+                    ':' => Ok(Token::Operator(OperatorKind::Colon)),
                     '\0' => Ok(Token::EOF),
                     _ => Err(ErrorToken::InvaildChar(curr)),
                 }
@@ -81,101 +169,293 @@ pub mod mylexer {
             token
         }
 
-        // 次のトークンが期待している演算子のときはトークンを一つ読み進める
-        // それ以外はErrorTokenで包んで返す
-        pub fn consume(&mut self, token: Token) -> Result<Token, ErrorToken> {
-            while self.current_char().is_whitespace() {
+        // 整数リテラル
+        // 数字で始まり英数字と.が続く塊を一つのリテラルとして読む
+        // 読み終えると最後の文字の位置にいる
+        fn read_number(&mut self) -> Result<Token, ErrorToken> {
+            let (line, column) = self.location();
+            let mut text = String::from(self.current_char());
+            while self.peek_char(1).is_ascii_alphanumeric() || self.peek_char(1) == '.' {
                 self.proceed_char(1);
+                text.push(self.current_char());
             }
 
-            let op = match token {
-                Token::Operator(o) => o,
-                Token::EOF => {
-                    let curr = self.current_char();
-                    if curr == '\0' {
-                        return Ok(Token::EOF);
-                    } else {
-                        return Err(ErrorToken::InvaildChar(curr));
-                    }
-                }
+            // 0x, 0bで始まれば16進数と2進数、0で始まれば8進数
+            let lower = text.to_ascii_lowercase();
+            let (radix, body) = if lower.starts_with("0x") {
+                (16, &text[2..])
+            } else if lower.starts_with("0b") {
+                (2, &text[2..])
+            } else if text.starts_with('0') {
+                (8, &text[..])
+            } else {
+                (10, &text[..])
+            };
+            let len = body
+                .find(|c: char| !c.is_digit(radix))
+                .unwrap_or(body.len());
+            let (digits, suffix) = body.split_at(len);
+            if digits.is_empty() {
+                return Err(ErrorToken::InvalidNumber(line, column));
+            }
+            let (unsigned, long) = match Self::number_suffix(suffix) {
+                Some(suffix) => suffix,
+                None => return Err(ErrorToken::InvalidNumber(line, column)),
+            };
+            let value = match u64::from_str_radix(digits, radix) {
+                Ok(value) => value,
+                Err(_) => return Err(ErrorToken::NumberOverflow(line, column)),
             };
 
-            let op_chars = format!("{}", op);
-            let op_chars_len = op_chars.len();
-
-            let mut curr = String::new();
-            let c = self.current_char();
-
-            for i in 0..op_chars_len {
-                curr.push_str(&self.peek_char(i).to_string()[..]);
+            // 値が収まる最初の型を選ぶ
+            // 10進数は接尾辞にuがなければ符号付きの型だけを候補にする
+            let decimal = radix == 10;
+            let candidates = [
+                (Type::Int, !long && !unsigned, i32::MAX as u64),
+                (Type::UInt, !long && (unsigned || !decimal), u32::MAX as u64),
+                (Type::Long, !unsigned, i64::MAX as u64),
+                (Type::ULong, unsigned || !decimal, u64::MAX),
+            ];
+            match candidates
+                .into_iter()
+                .find(|(_, allowed, max)| *allowed && value <= *max)
+            {
+                Some((ty, _, _)) => {
+                    Ok(Token::Operator(OperatorKind::Operand(value as NumType, ty)))
+                }
+                None => Err(ErrorToken::NumberOverflow(line, column)),
             }
+        }
 
-            if curr == op_chars {
-                self.proceed_char(op_chars_len);
-                Ok(Token::Operator(op))
+        // 整数リテラルの接尾辞
+        // u, l, ll とその組み合わせを(unsigned, long)にする
+        // longは8バイトなのでllはlと同じ扱いになる
+        fn number_suffix(suffix: &str) -> Option<(bool, bool)> {
+            let (unsigned, rest) = if let Some(rest) = suffix.strip_prefix(['u', 'U']) {
+                (true, rest)
+            } else if let Some(rest) = suffix.strip_suffix(['u', 'U']) {
+                (true, rest)
             } else {
-                Err(ErrorToken::InvaildChar(c))
+                (false, suffix)
+            };
+            match rest {
+                "" => Some((unsigned, false)),
+                "l" | "L" | "ll" | "LL" => Some((unsigned, true)),
+                _ => None,
             }
         }
 
-        pub fn consume_ident(&mut self) -> Result<Token, ErrorToken> {
-            while self.current_char().is_whitespace() {
-                self.proceed_char(1);
+        // "..."
+        // 読み終えると閉じる"の位置にいる
+        fn read_string(&mut self) -> Result<Token, ErrorToken> {
+            let (line, column) = self.location();
+            let mut bytes = Vec::new();
+            self.proceed_char(1);
+            loop {
+                match self.current_char() {
+                    '"' => break,
+                    '\\' => {
+                        self.proceed_char(1);
+                        bytes.push(self.read_escape()?);
+                    }
+                    '\n' => return Err(ErrorToken::UnterminatedLiteral(line, column)),
+                    _ if self.position >= self.input.len() => {
+                        return Err(ErrorToken::UnterminatedLiteral(line, column))
+                    }
+                    c => {
+                        let mut buf = [0; 4];
+                        bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                        self.proceed_char(1);
+                    }
+                }
             }
+            Ok(Token::Operator(OperatorKind::StringLiteral(bytes)))
+        }
 
-            let curr = self.current_char();
-
-            let token = if Self::is_ident_char(&curr) {
-                let mut ident = vec![curr];
-                while Self::is_ident_char(&self.peek_char(1)) {
+        // 'c'
+        // 読み終えると閉じる'の位置にいる
+        fn read_char(&mut self) -> Result<Token, ErrorToken> {
+            let (line, column) = self.location();
+            self.proceed_char(1);
+            let c = match self.current_char() {
+                '\\' => {
                     self.proceed_char(1);
-                    ident.push(self.current_char());
+                    self.read_escape()?
+                }
+                '\'' => return Err(ErrorToken::EmptyCharLiteral(line, column)),
+                '\n' => return Err(ErrorToken::UnterminatedLiteral(line, column)),
+                c => {
+                    self.proceed_char(1);
+                    c as u8
                 }
-                Ok(Token::Operator(OperatorKind::Ident(Ident::new(
-                    &ident.iter().collect::<String>(),
-                ))))
-            } else {
-                return Err(ErrorToken::InvaildChar(curr));
             };
-
-            token
+            if self.current_char() != '\'' {
+                // 同じ行で閉じられていれば二文字以上のリテラル
+                let closed = self.input[self.position..]
+                    .iter()
+                    .take_while(|c| **c != '\n')
+                    .any(|c| *c == '\'');
+                if closed {
+                    return Err(ErrorToken::MultiCharLiteral(line, column));
+                }
+                return Err(ErrorToken::UnterminatedLiteral(line, column));
+            }
+            Ok(Token::Operator(OperatorKind::CharLiteral(c)))
         }
 
-        // 次のトークンが期待している演算子のときはトークンを一つ読み進める
-        pub fn expect(&mut self, token: Token) -> bool {
-            while self.current_char().is_whitespace() {
+        // \の次の文字からエスケープシーケンスを一つ読む
+        // \101のような8進数は3桁まで、\x41のような16進数は続く限り読む
+        // 不正なものは\の位置を返す
+        fn read_escape(&mut self) -> Result<u8, ErrorToken> {
+            let (line, column) = self.location_of(self.position - 1);
+            let c = self.current_char();
+            if c.is_digit(8) {
+                let mut value: u32 = 0;
+                for _ in 0..3 {
+                    match self.current_char().to_digit(8) {
+                        Some(d) => value = value * 8 + d,
+                        None => break,
+                    }
+                    self.proceed_char(1);
+                }
+                return Ok(value as u8);
+            }
+            if c == 'x' {
                 self.proceed_char(1);
+                if !self.current_char().is_ascii_hexdigit() {
+                    return Err(ErrorToken::InvalidEscape(c, line, column));
+                }
+                let mut value: u32 = 0;
+                while let Some(d) = self.current_char().to_digit(16) {
+                    value = value.wrapping_mul(16).wrapping_add(d);
+                    self.proceed_char(1);
+                }
+                return Ok(value as u8);
             }
+            self.proceed_char(1);
+            let value = match c {
+                'a' => 0x07,
+                'b' => 0x08,
+                't' => b'\t',
+                'n' => b'\n',
+                'v' => 0x0b,
+                'f' => 0x0c,
+                'r' => b'\r',
+                'e' => 0x1b,
+                '\\' | '\'' | '"' | '?' => c as u8,
+                _ => return Err(ErrorToken::InvalidEscape(c, line, column)),
+            };
+            Ok(value)
+        }
 
-            let op = match token {
-                Token::Operator(o) => o,
-                Token::EOF => {
-                    let curr = self.current_char();
-                    return curr == '\0';
+        // 次のトークンが期待しているトークンのときはトークンを一つ読み進める
+        // それ以外はErrorTokenで包んで返す
+        pub fn consume(&mut self, token: Token) -> Result<Token, ErrorToken> {
+            self.skip_trivia();
+
+            let position = self.position;
+            let c = self.current_char();
+
+            // 一旦トークンを読み、期待と異なれば位置を戻す
+            // 文字列の前方一致では "<=" と "<" や "return" と "returns" を区別できない
+            match self.next_token() {
+                Ok(t) if t == token => Ok(t),
+                _ => {
+                    self.position = position;
+                    Err(ErrorToken::InvaildChar(c))
                 }
-            };
+            }
+        }
 
-            let op_chars = format!("{}", op);
-            let op_chars_len = op_chars.len();
+        // 次のトークンが識別子のときはトークンを一つ読み進める
+        pub fn consume_ident(&mut self) -> Result<Token, ErrorToken> {
+            self.skip_trivia();
 
-            let mut curr = String::new();
+            let position = self.position;
+            let c = self.current_char();
 
-            for i in 0..op_chars_len {
-                curr.push_str(&self.peek_char(i).to_string()[..]);
+            match self.next_token() {
+                Ok(Token::Operator(OperatorKind::Ident(i))) => {
+                    Ok(Token::Operator(OperatorKind::Ident(i)))
+                }
+                _ => {
+                    self.position = position;
+                    Err(ErrorToken::InvaildChar(c))
+                }
             }
+        }
+
+        // 次のトークンが期待しているトークンかどうかを返す
+        // 入力は読み進めない
+        pub fn expect(&mut self, token: Token) -> bool {
+            self.skip_trivia();
 
-            curr == op_chars
+            let position = self.position;
+            let result = matches!(self.next_token(), Ok(t) if t == token);
+            self.position = position;
+            result
         }
 
         pub fn expect_ident(&mut self) -> bool {
-            while self.current_char().is_whitespace() {
-                self.proceed_char(1);
-            }
+            self.skip_trivia();
 
             let curr = self.current_char();
             Self::is_ident_char(&curr)
         }
 
+        // 現在の読み取り位置
+        // 先読みして構文を判定した後、restoreで戻すのに使う
+        pub fn save(&self) -> usize {
+            self.position
+        }
+
+        pub fn restore(&mut self, position: usize) {
+            self.position = position;
+        }
+
+        // 次のトークンのソース上の位置を(行, 列)で返す
+        // どちらも1から数える
+        pub fn location(&mut self) -> (usize, usize) {
+            self.skip_trivia();
+
+            self.location_of(self.position)
+        }
+
+        fn location_of(&self, position: usize) -> (usize, usize) {
+            let before = &self.input[..position.min(self.input.len())];
+            let line = before.iter().filter(|c| **c == '\n').count() + 1;
+            let column = before.iter().rev().take_while(|c| **c != '\n').count() + 1;
+            (line, column)
+        }
+
+        // 空白と // から行末まで、/* から */ までのコメントを読み飛ばす
+        // 閉じられていない /* は読み飛ばさず、その位置で止まる
+        fn skip_trivia(&mut self) {
+            loop {
+                let c = self.current_char();
+                if c.is_whitespace() {
+                    self.proceed_char(1);
+                } else if c == '/' && self.peek_char(1) == '/' {
+                    while !matches!(self.current_char(), '\n' | '\0') {
+                        self.proceed_char(1);
+                    }
+                } else if c == '/' && self.peek_char(1) == '*' {
+                    let start = self.position;
+                    self.proceed_char(2);
+                    while !(self.current_char() == '*' && self.peek_char(1) == '/') {
+                        if self.position >= self.input.len() {
+                            self.position = start;
+                            return;
+                        }
+                        self.proceed_char(1);
+                    }
+                    self.proceed_char(2);
+                } else {
+                    break;
+                }
+            }
+        }
+
         // 入力n分だけ読み進める
         fn proceed_char(&mut self, n: usize) {
             self.position += n;
@@ -198,71 +478,149 @@ pub mod mylexer {
             }
         }
 
-        // 数字の判定
-        // 1.1.1のような文字に対応できるよう修正が必要
+        // 整数リテラルの始まり
         fn is_number(c: &char) -> bool {
-            c.is_ascii_digit() || c == &'.'
+            c.is_ascii_digit()
         }
 
         fn is_ident_char(c: &char) -> bool {
             c.is_alphabetic() || c == &'_'
         }
 
-        fn push_ident(&mut self, ident: Ident) {
-            self.idents.push(ident);
+        // 識別子の2文字目以降は数字も使える
+        fn is_ident_tail_char(c: &char) -> bool {
+            Self::is_ident_char(c) || c.is_ascii_digit()
         }
 
-        pub fn calc_offset(&mut self, ident: Ident) -> usize {
-            match self.idents.iter().position(|i| *i == ident) {
-                Some(index) => (index + 1) * 8,
-                None => {
-                    self.push_ident(ident);
-                    self.idents.len() * 8
-                }
-            }
+        // 現在のスコープに変数を作る
+        // 同じスコープで名前が使われていればNoneを返す
+        pub fn declare_var(&mut self, ident: Ident, ty: Type) -> Option<Var> {
+            self.symbols.declare(&ident.name, ty)
+        }
+
+        // ファイルスコープに変数を作る
+        pub fn declare_global_var(&mut self, ident: Ident, ty: Type) -> Option<Var> {
+            self.symbols.declare_global(&ident.name, ty)
+        }
+
+        pub fn find_var(&mut self, ident: &Ident) -> Option<Var> {
+            self.symbols.find(&ident.name).cloned()
+        }
+
+        // 現在のスコープに列挙子を作る
+        // 同じスコープで名前が使われていればfalseを返す
+        pub fn declare_enum(&mut self, ident: &Ident, value: i64) -> bool {
+            self.symbols.declare_enum(&ident.name, value)
+        }
+
+        pub fn find_enum(&mut self, ident: &Ident) -> Option<i64> {
+            self.symbols.find_enum(&ident.name)
+        }
+
+        // 現在のスコープにtypedef名を作る
+        // 同じスコープで名前が使われていればfalseを返す
+        pub fn declare_typedef(&mut self, ident: &Ident, ty: Type) -> bool {
+            self.symbols.declare_typedef(&ident.name, ty)
+        }
+
+        pub fn find_typedef(&mut self, ident: &Ident) -> Option<Type> {
+            self.symbols.find_typedef(&ident.name).cloned()
+        }
+
+        pub fn declare_tag(&mut self, ident: &Ident, ty: Type) {
+            self.symbols.declare_tag(&ident.name, ty);
+        }
+
+        pub fn find_tag(&mut self, ident: &Ident) -> Option<Type> {
+            self.symbols.find_tag(&ident.name).cloned()
+        }
+
+        pub fn find_local_tag(&mut self, ident: &Ident) -> Option<Type> {
+            self.symbols.find_local_tag(&ident.name).cloned()
+        }
+
+        pub fn declare_function(&mut self, ident: &Ident, ty: Type) {
+            self.symbols.declare_function(&ident.name, ty);
         }
 
-        pub fn get_ident_count(&mut self) -> usize {
-            self.idents.len()
+        // 関数の戻り値の型を返す
+        // 定義されていない関数はintを返すものとみなす
+        pub fn find_function(&mut self, ident: &Ident) -> Type {
+            self.symbols
+                .find_function(&ident.name)
+                .and_then(|ty| ty.return_type())
+                .cloned()
+                .unwrap_or(Type::Int)
+        }
+
+        // 関数名を値として使うときの変数
+        pub fn find_function_var(&mut self, ident: &Ident) -> Option<Var> {
+            self.symbols.find_function_var(&ident.name)
+        }
+
+        // 文字列リテラルを登録してラベルの番号を返す
+        pub fn add_string(&mut self, bytes: Vec<u8>) -> usize {
+            self.symbols.add_string(bytes)
+        }
+
+        pub fn take_strings(&mut self) -> Vec<Vec<u8>> {
+            self.symbols.take_strings()
+        }
+
+        pub fn enter_function(&mut self) {
+            self.symbols.enter_function();
+        }
+
+        // 関数の変数の領域として確保すべきバイト数を返す
+        pub fn leave_function(&mut self) -> usize {
+            self.symbols.leave_function()
+        }
+
+        pub fn enter_scope(&mut self) {
+            self.symbols.enter_scope();
+        }
+
+        pub fn leave_scope(&mut self) {
+            self.symbols.leave_scope();
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{lexer::mylexer::Lexer, token::mytoken::*};
+    use crate::{lexer::mylexer::Lexer, token::mytoken::*, types::mytype::Type};
 
     #[test]
     fn test_lexer() {
-        let mut lexer = Lexer::new("1 +10 - 2*3 + 6/2a == < >= != $;");
+        let mut lexer = Lexer::new("1 +10 - 2*3 + 6/2 a == < >= != $;");
         assert_eq!(
             lexer.next_token(),
-            Ok(Token::Operator(OperatorKind::Operand(1)))
+            Ok(Token::Operator(OperatorKind::Operand(1, Type::Int)))
         );
         assert_eq!(lexer.next_token(), Ok(Token::Operator(OperatorKind::Add)));
         assert_eq!(
             lexer.next_token(),
-            Ok(Token::Operator(OperatorKind::Operand(10)))
+            Ok(Token::Operator(OperatorKind::Operand(10, Type::Int)))
         );
         assert_eq!(lexer.next_token(), Ok(Token::Operator(OperatorKind::Sub)));
         assert_eq!(
             lexer.next_token(),
-            Ok(Token::Operator(OperatorKind::Operand(2)))
+            Ok(Token::Operator(OperatorKind::Operand(2, Type::Int)))
         );
         assert_eq!(lexer.next_token(), Ok(Token::Operator(OperatorKind::Mul)));
         assert_eq!(
             lexer.next_token(),
-            Ok(Token::Operator(OperatorKind::Operand(3)))
+            Ok(Token::Operator(OperatorKind::Operand(3, Type::Int)))
         );
         assert_eq!(lexer.next_token(), Ok(Token::Operator(OperatorKind::Add)));
         assert_eq!(
             lexer.next_token(),
-            Ok(Token::Operator(OperatorKind::Operand(6)))
+            Ok(Token::Operator(OperatorKind::Operand(6, Type::Int)))
         );
         assert_eq!(lexer.next_token(), Ok(Token::Operator(OperatorKind::Div)));
         assert_eq!(
             lexer.next_token(),
-            Ok(Token::Operator(OperatorKind::Operand(2)))
+            Ok(Token::Operator(OperatorKind::Operand(2, Type::Int)))
         );
         assert_eq!(
             lexer.next_token(),
@@ -285,4 +643,151 @@ mod test {
         assert_eq!(lexer.next_token(), Ok(Token::Operator(OperatorKind::Semi)));
         assert_eq!(lexer.next_token(), Ok(Token::EOF));
     }
+
+    #[test]
+    fn test_keyword() {
+        let mut lexer = Lexer::new("return returns;");
+        assert_eq!(lexer.next_token(), Ok(Token::Keyword(KeywordKind::Return)));
+        assert_eq!(
+            lexer.next_token(),
+            Ok(Token::Operator(OperatorKind::Ident(Ident::new("returns"))))
+        );
+        assert_eq!(lexer.next_token(), Ok(Token::Operator(OperatorKind::Semi)));
+        assert_eq!(lexer.next_token(), Ok(Token::EOF));
+    }
+
+    #[test]
+    fn test_literal() {
+        let mut lexer = Lexer::new(r#""a\n\t\\\x41\101\0\"" 'b' '\'' '\x7f' "abc"#);
+        assert_eq!(
+            lexer.next_token(),
+            Ok(Token::Operator(OperatorKind::StringLiteral(
+                b"a\n\t\\AA\0\"".to_vec()
+            )))
+        );
+        assert_eq!(
+            lexer.next_token(),
+            Ok(Token::Operator(OperatorKind::CharLiteral(b'b')))
+        );
+        assert_eq!(
+            lexer.next_token(),
+            Ok(Token::Operator(OperatorKind::CharLiteral(b'\'')))
+        );
+        assert_eq!(
+            lexer.next_token(),
+            Ok(Token::Operator(OperatorKind::CharLiteral(0x7f)))
+        );
+        // 閉じられていない文字列は開始位置を返す
+        assert_eq!(
+            lexer.next_token(),
+            Err(ErrorToken::UnterminatedLiteral(1, 39))
+        );
+
+        // 不正なエスケープは\の位置を返す
+        let mut lexer = Lexer::new(r#""ab\q""#);
+        assert_eq!(
+            lexer.next_token(),
+            Err(ErrorToken::InvalidEscape('q', 1, 4))
+        );
+        let mut lexer = Lexer::new("\n  '\\xg'");
+        assert_eq!(
+            lexer.next_token(),
+            Err(ErrorToken::InvalidEscape('x', 2, 4))
+        );
+
+        // 空の文字リテラルと二文字以上の文字リテラル
+        let mut lexer = Lexer::new("''");
+        assert_eq!(lexer.next_token(), Err(ErrorToken::EmptyCharLiteral(1, 1)));
+        let mut lexer = Lexer::new(" 'ab'");
+        assert_eq!(lexer.next_token(), Err(ErrorToken::MultiCharLiteral(1, 2)));
+        let mut lexer = Lexer::new("'ab\n'");
+        assert_eq!(
+            lexer.next_token(),
+            Err(ErrorToken::UnterminatedLiteral(1, 1))
+        );
+    }
+
+    #[test]
+    fn test_comment() {
+        let mut lexer = Lexer::new("1 // a / b\n/* c\n * d */ + /**/2 /* \"*/ ;");
+        assert_eq!(
+            lexer.next_token(),
+            Ok(Token::Operator(OperatorKind::Operand(1, Type::Int)))
+        );
+        assert!(lexer.expect(Token::Operator(OperatorKind::Add)));
+        assert_eq!(lexer.location(), (3, 9));
+        assert!(lexer.consume(Token::Operator(OperatorKind::Add)).is_ok());
+        assert_eq!(
+            lexer.next_token(),
+            Ok(Token::Operator(OperatorKind::Operand(2, Type::Int)))
+        );
+        assert_eq!(lexer.next_token(), Ok(Token::Operator(OperatorKind::Semi)));
+        assert_eq!(lexer.next_token(), Ok(Token::EOF));
+
+        // 文字列の中の // はコメントではない
+        let mut lexer = Lexer::new("\"//\" /");
+        assert_eq!(
+            lexer.next_token(),
+            Ok(Token::Operator(OperatorKind::StringLiteral(b"//".to_vec())))
+        );
+        assert_eq!(lexer.next_token(), Ok(Token::Operator(OperatorKind::Div)));
+
+        // 閉じられていないコメントは開始位置を返し、読み進めない
+        let mut lexer = Lexer::new("1\n  /* a */ /* b *");
+        assert!(lexer.next_token().is_ok());
+        assert_eq!(lexer.location(), (2, 11));
+        assert_eq!(
+            lexer.next_token(),
+            Err(ErrorToken::UnterminatedComment(2, 11))
+        );
+        assert!(!lexer.expect(Token::EOF));
+        assert_eq!(
+            lexer.next_token(),
+            Err(ErrorToken::UnterminatedComment(2, 11))
+        );
+    }
+
+    #[test]
+    fn test_number() {
+        let number = |input: &str| Lexer::new(input).next_token();
+        let operand = |n: usize, ty: Type| Ok(Token::Operator(OperatorKind::Operand(n, ty)));
+        assert_eq!(number("0x1F"), operand(31, Type::Int));
+        assert_eq!(number("017"), operand(15, Type::Int));
+        assert_eq!(number("0"), operand(0, Type::Int));
+        assert_eq!(number("0b101"), operand(5, Type::Int));
+        assert_eq!(number("2147483648"), operand(2147483648, Type::Long));
+        assert_eq!(number("0x80000000"), operand(0x80000000, Type::UInt));
+        assert_eq!(number("0x100000000"), operand(0x100000000, Type::Long));
+        assert_eq!(
+            number("0xffffffffffffffff"),
+            operand(u64::MAX as usize, Type::ULong)
+        );
+        assert_eq!(number("1u"), operand(1, Type::UInt));
+        assert_eq!(number("4294967296U"), operand(4294967296, Type::ULong));
+        assert_eq!(number("1l"), operand(1, Type::Long));
+        assert_eq!(number("1LL"), operand(1, Type::Long));
+        assert_eq!(number("1uLL"), operand(1, Type::ULong));
+        assert_eq!(number("1llu"), operand(1, Type::ULong));
+
+        // 不正なリテラルは開始位置を返す
+        assert_eq!(number(" 1.2.3"), Err(ErrorToken::InvalidNumber(1, 2)));
+        assert_eq!(number("08"), Err(ErrorToken::InvalidNumber(1, 1)));
+        assert_eq!(number("0x"), Err(ErrorToken::InvalidNumber(1, 1)));
+        assert_eq!(number("0b12"), Err(ErrorToken::InvalidNumber(1, 1)));
+        assert_eq!(number("12abc"), Err(ErrorToken::InvalidNumber(1, 1)));
+        assert_eq!(number("1uu"), Err(ErrorToken::InvalidNumber(1, 1)));
+        assert_eq!(number("1lL"), Err(ErrorToken::InvalidNumber(1, 1)));
+        assert_eq!(
+            number("99999999999999999999"),
+            Err(ErrorToken::NumberOverflow(1, 1))
+        );
+        assert_eq!(
+            number("9223372036854775808"),
+            Err(ErrorToken::NumberOverflow(1, 1))
+        );
+        assert_eq!(
+            number("9223372036854775808u"),
+            operand(9223372036854775808, Type::ULong)
+        );
+    }
 }
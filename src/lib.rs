@@ -1,11 +1,14 @@
 pub mod architecture;
+pub mod constant;
 pub mod error;
 pub mod generator;
 pub mod lexer;
 pub mod numtype;
 pub mod parser;
+pub mod symbol;
 pub mod token;
 pub mod tree;
+pub mod types;
 
 use std::{
     fs::File,
@@ -109,25 +112,25 @@ fn construct_assembly(contents: &str) -> Result<String, MyError> {
     let lexer = &mut Lexer::new(contents);
 
     // 構文解析
-    let (trees, lexer) = program(lexer);
-    let ident_count = lexer.get_ident_count();
+    let program = program(lexer);
 
     // prologue
     assembly.push_str(&program_prologue());
 
-    // main関数
-    assembly.push_str(&main_func());
+    // グローバル変数
+    for global in program.globals {
+        generate_global(&mut assembly, global);
+    }
 
-    // 変数の領域を確保
-    assembly.push_str(&memory_allocate(ident_count * 8));
+    // 文字列リテラル
+    generate_strings(&mut assembly, program.strings);
 
     // 構文木をアセンブリに変換
-    for tree in trees {
-        generate_assembly(&mut assembly, tree);
-        assembly.push_str(&stmt_epilogue());
+    assembly.push_str(&section(Section::Text));
+    let context = &mut Context::new();
+    for function in program.functions {
+        generate_function(&mut assembly, context, function)?;
     }
 
-    // 最後の式の結果がraxに残り、返される
-    assembly.push_str(&program_epilogue());
     Ok(assembly)
 }
@@ -1,39 +1,1199 @@
 pub mod myparser {
     use std::process::exit;
 
+    use crate::constant::myconstant::{eval, normalize};
     use crate::lexer::mylexer::Lexer;
+    use crate::numtype::mynumtype::NumType;
+    use crate::symbol::mysymbol::Var;
     use crate::token::mytoken::*;
     use crate::tree::mytree::*;
+    use crate::types::mytype::{Aggregate, Type};
+
+    // 読んでいる関数の中だけで使う状態
+    // 関数ごとに作り直す
+    struct FunctionState {
+        // 読んでいる途中のswitch文
+        // 内側のものほど末尾に積まれる
+        switches: Vec<Cases>,
+        // 定義したラベルと、gotoで参照したラベルとその位置
+        labels: Vec<String>,
+        gotos: Vec<(String, (usize, usize))>,
+    }
+
+    impl FunctionState {
+        fn new() -> FunctionState {
+            FunctionState {
+                switches: vec![],
+                labels: vec![],
+                gotos: vec![],
+            }
+        }
+
+        fn enter_switch(&mut self, ty: Type) {
+            self.switches.push(Cases::new(ty));
+        }
+
+        fn leave_switch(&mut self) -> Cases {
+            self.switches.pop().unwrap()
+        }
+
+        // 一番内側のswitch文
+        // switch文の外ではNoneを返す
+        fn current_switch(&mut self) -> Option<&mut Cases> {
+            self.switches.last_mut()
+        }
+
+        // ラベルを定義する
+        // 同じ関数に同じ名前のラベルがあればfalseを返す
+        fn define_label(&mut self, name: &str) -> bool {
+            if self.labels.iter().any(|l| l == name) {
+                return false;
+            }
+            self.labels.push(name.to_string());
+            true
+        }
+
+        // gotoの飛び先は関数の後ろで定義されることもあるので、関数を読み終えてから調べる
+        fn use_label(&mut self, name: &str, location: (usize, usize)) {
+            self.gotos.push((name.to_string(), location));
+        }
+
+        // 定義されていないラベルへのgotoがあれば返す
+        // 関数を読み終えたときに呼ぶ
+        fn undefined_label(&self) -> Option<(String, (usize, usize))> {
+            self.gotos
+                .iter()
+                .find(|(name, _)| !self.labels.contains(name))
+                .cloned()
+        }
+    }
 
     // プログラム
-    pub fn program(lexer: &mut Lexer) -> (Vec<Tree>, &mut Lexer) {
-        let mut trees = Vec::new();
+    // (function | global_variable)*
+    pub fn program(lexer: &mut Lexer) -> Program {
+        let mut functions = Vec::new();
+        let mut globals = Vec::new();
         while !lexer.expect(Token::EOF) {
-            trees.push(stmt(lexer));
+            check_comment(lexer);
+            if lexer.consume(Token::Keyword(KeywordKind::Typedef)).is_ok() {
+                typedef_decl(lexer);
+                continue;
+            }
+            let basety = basetype(lexer);
+            // struct tag { ... }; のように型だけを宣言することもある
+            if lexer.consume(Token::Operator(OperatorKind::Semi)).is_ok() {
+                continue;
+            }
+            // "*"* ident ( で始まれば関数定義になる
+            let position = lexer.save();
+            let ty = pointers(lexer, basety.clone());
+            if let Ok(Token::Operator(OperatorKind::Ident(ident))) = lexer.consume_ident() {
+                if lexer.consume(Token::Operator(OperatorKind::LParen)).is_ok() {
+                    functions.push(function(lexer, ident, ty));
+                    continue;
+                }
+            }
+            lexer.restore(position);
+            let location = lexer.location();
+            let (ident, ty) = declarator(lexer, basety.clone());
+            global_variable(lexer, &mut globals, basety, (ident, ty), location);
+        }
+        let strings = lexer.take_strings();
+        Program {
+            functions,
+            globals,
+            strings,
+        }
+    }
+
+    // グローバル変数の定義
+    // basetype declarator (= const_expr)? (, declarator (= const_expr)?)* ;
+    // 最初の宣言子はprogramで読み終えている
+    fn global_variable(
+        lexer: &mut Lexer,
+        globals: &mut Vec<GlobalVar>,
+        basety: Type,
+        (mut ident, mut ty): (Ident, Type),
+        mut location: (usize, usize),
+    ) {
+        loop {
+            if !ty.is_complete() {
+                error_at(location, "variable has incomplete type");
+            }
+            let name = ident.name.clone();
+            let var = match lexer.declare_global_var(ident, ty) {
+                Some(var) => var,
+                None => error_at(location, &format!("redefinition of '{}'", name)),
+            };
+            let init = if lexer.consume(Token::Operator(OperatorKind::Equal)).is_ok() {
+                if var.ty.is_array() {
+                    error_at(lexer.location(), "array initializer is not supported");
+                }
+                if var.ty.is_struct() {
+                    error_at(lexer.location(), "struct initializer is not supported");
+                }
+                // 初期値は変数の型に切り詰めてから置く
+                Some(normalize(const_expr(lexer), &var.ty))
+            } else {
+                None
+            };
+            globals.push(GlobalVar { var, init });
+            if lexer.consume(Token::Operator(OperatorKind::Semi)).is_ok() {
+                return;
+            }
+            if lexer.consume(Token::Operator(OperatorKind::Comma)).is_err() {
+                eprintln!("expect ',' or ';' but disappear");
+                exit(1);
+            }
+            location = lexer.location();
+            (ident, ty) = declarator(lexer, basety.clone());
+        }
+    }
+
+    // 関数定義
+    // basetype declarator(basetype declarator, ...) { stmt* }
+    // 引数の(まではprogramで読み終えている
+    fn function(lexer: &mut Lexer, ident: Ident, ty: Type) -> Function {
+        if ty.is_struct() {
+            eprintln!("returning struct by value is not supported");
+            exit(1);
+        }
+        lexer.enter_function();
+        let mut params = Vec::new();
+        let void = void_params(lexer);
+        while !void
+            && lexer
+                .consume(Token::Operator(OperatorKind::RParen))
+                .is_err()
+        {
+            if !params.is_empty() && lexer.consume(Token::Operator(OperatorKind::Comma)).is_err() {
+                eprintln!("expect ',' but disappear");
+                exit(1);
+            }
+            let ty = basetype(lexer);
+            let location = lexer.location();
+            let (ident, ty) = declarator(lexer, ty);
+            if ty.is_struct() {
+                error_at(location, "passing struct by value is not supported");
+            }
+            params.push(declare_var(lexer, ident, param_type(ty), location));
+        }
+        // 再帰呼び出しできるよう本体より先に登録する
+        let param_types = params.iter().map(|var| var.ty.clone()).collect();
+        lexer.declare_function(&ident, Type::func(ty.clone(), param_types));
+
+        if lexer
+            .consume(Token::Operator(OperatorKind::LBrace))
+            .is_err()
+        {
+            eprintln!("expect '{{' but disappear");
+            exit(1);
+        }
+        let mut func = FunctionState::new();
+        let body = compound_stmt(lexer, &mut func);
+        if let Some((name, location)) = func.undefined_label() {
+            error_at(location, &format!("label '{}' used but not defined", name));
+        }
+        let stack_size = lexer.leave_function();
+
+        Function {
+            name: ident.name,
+            ty,
+            params,
+            body,
+            stack_size,
+        }
+    }
+
+    // 識別子を読む
+    fn get_ident(lexer: &mut Lexer) -> Ident {
+        match lexer.consume_ident() {
+            Ok(Token::Operator(OperatorKind::Ident(i))) => i,
+            _ => {
+                eprintln!("expect identifier but disappear");
+                exit(1);
+            }
+        }
+    }
+
+    // 次のトークンが型名かどうか
+    // 識別子はtypedef名として宣言されていれば型名になる
+    fn is_typename(lexer: &mut Lexer) -> bool {
+        let keyword = [
+            KeywordKind::Char,
+            KeywordKind::Short,
+            KeywordKind::Int,
+            KeywordKind::Long,
+            KeywordKind::Signed,
+            KeywordKind::Unsigned,
+            KeywordKind::Struct,
+            KeywordKind::Union,
+            KeywordKind::Enum,
+            KeywordKind::Typedef,
+        ]
+        .into_iter()
+        .any(|k| lexer.expect(Token::Keyword(k)));
+        keyword || typedef_name(lexer).is_some()
+    }
+
+    // 次のトークンがtypedef名であればその型を返す
+    // 入力は読み進めない
+    // ident : はラベルなのでtypedef名として扱わない
+    fn typedef_name(lexer: &mut Lexer) -> Option<Type> {
+        let position = lexer.save();
+        let ty = match lexer.consume_ident() {
+            Ok(Token::Operator(OperatorKind::Ident(ident))) => lexer.find_typedef(&ident),
+            _ => None,
+        };
+        let label = lexer.expect(Token::Operator(OperatorKind::Colon));
+        lexer.restore(position);
+        ty.filter(|_| !label)
+    }
+
+    // typedef basetype declarator (, declarator)* ;
+    // 宣言子の名前を型の別名として現在のスコープに登録する
+    fn typedef_decl(lexer: &mut Lexer) {
+        let basety = basetype(lexer);
+        if lexer.consume(Token::Operator(OperatorKind::Semi)).is_ok() {
+            return;
+        }
+        loop {
+            let location = lexer.location();
+            let (ident, ty) = declarator(lexer, basety.clone());
+            if !lexer.declare_typedef(&ident, ty) {
+                error_at(location, &format!("redefinition of '{}'", ident.name));
+            }
+            if lexer.consume(Token::Operator(OperatorKind::Semi)).is_ok() {
+                return;
+            }
+            if lexer.consume(Token::Operator(OperatorKind::Comma)).is_err() {
+                eprintln!("expect ',' or ';' but disappear");
+                exit(1);
+            }
+        }
+    }
+
+    // 型指定子
+    // char, short (int), int, long (int), long long (int), struct_decl, enum_decl, typedef名
+    fn basetype(lexer: &mut Lexer) -> Type {
+        if let Some(ty) = typedef_name(lexer) {
+            get_ident(lexer);
+            return ty;
+        }
+        if lexer.consume(Token::Keyword(KeywordKind::Struct)).is_ok() {
+            return struct_decl(lexer, false);
+        }
+        if lexer.consume(Token::Keyword(KeywordKind::Union)).is_ok() {
+            return struct_decl(lexer, true);
+        }
+        if lexer.consume(Token::Keyword(KeywordKind::Enum)).is_ok() {
+            return enum_decl(lexer);
+        }
+        let (mut chars, mut shorts, mut ints, mut longs) = (0, 0, 0, 0);
+        let (mut signeds, mut unsigneds) = (0, 0);
+        loop {
+            if lexer.consume(Token::Keyword(KeywordKind::Signed)).is_ok() {
+                signeds += 1;
+            } else if lexer.consume(Token::Keyword(KeywordKind::Unsigned)).is_ok() {
+                unsigneds += 1;
+            } else if lexer.consume(Token::Keyword(KeywordKind::Char)).is_ok() {
+                chars += 1;
+            } else if lexer.consume(Token::Keyword(KeywordKind::Short)).is_ok() {
+                shorts += 1;
+            } else if lexer.consume(Token::Keyword(KeywordKind::Int)).is_ok() {
+                ints += 1;
+            } else if lexer.consume(Token::Keyword(KeywordKind::Long)).is_ok() {
+                longs += 1;
+            } else {
+                break;
+            }
+        }
+
+        // signed, unsignedだけの場合はintになる
+        let ty = match (chars, shorts, ints, longs) {
+            (1, 0, 0, 0) => Type::Char,
+            (0, 1, 0 | 1, 0) => Type::Short,
+            (0, 0, 1, 0) => Type::Int,
+            (0, 0, 0 | 1, 1 | 2) => Type::Long,
+            (0, 0, 0, 0) if signeds + unsigneds > 0 => Type::Int,
+            (0, 0, 0, 0) => {
+                eprintln!("expect type name but disappear");
+                exit(1);
+            }
+            _ => {
+                eprintln!("invalid combination of type specifiers");
+                exit(1);
+            }
+        };
+        match (signeds, unsigneds) {
+            (0, 0) | (1, 0) => ty,
+            (0, 1) => match ty {
+                Type::Char => Type::UChar,
+                Type::Short => Type::UShort,
+                Type::Int => Type::UInt,
+                _ => Type::ULong,
+            },
+            _ => {
+                eprintln!("invalid combination of type specifiers");
+                exit(1);
+            }
+        }
+    }
+
+    // 構造体、共用体
+    // (struct | union) ident? { struct_member* }
+    // (struct | union) ident
+    // 構造体と共用体のタグは同じ名前空間を使う
+    fn struct_decl(lexer: &mut Lexer, is_union: bool) -> Type {
+        let location = lexer.location();
+        let tag = match lexer.consume_ident() {
+            Ok(Token::Operator(OperatorKind::Ident(ident))) => Some(ident),
+            _ => None,
+        };
+        let kind = if is_union { "union" } else { "struct" };
+        let wrong_kind = |tag: &Ident| -> ! {
+            error_at(
+                location,
+                &format!("'{}' defined as wrong kind of tag", tag.name),
+            )
+        };
+        if lexer
+            .consume(Token::Operator(OperatorKind::LBrace))
+            .is_err()
+        {
+            let tag = match tag {
+                Some(tag) => tag,
+                None => error_at(location, &format!("expected '{{' after {}", kind)),
+            };
+            // まだ宣言されていないタグは不完全な型として宣言しておく
+            match lexer.find_tag(&tag) {
+                Some(Type::Struct(aggregate)) if aggregate.is_union == is_union => {
+                    return Type::Struct(aggregate)
+                }
+                Some(_) => wrong_kind(&tag),
+                None => {}
+            }
+            let ty = Type::Struct(Aggregate::new(Some(tag.name.clone()), is_union));
+            lexer.declare_tag(&tag, ty.clone());
+            return ty;
+        }
+
+        // 同じスコープで先に宣言された不完全な型があれば、それを定義する
+        let aggregate = match &tag {
+            Some(tag) => match lexer.find_local_tag(tag) {
+                Some(Type::Struct(aggregate)) if aggregate.is_union != is_union => wrong_kind(tag),
+                Some(Type::Struct(aggregate)) if !aggregate.is_complete() => aggregate,
+                Some(Type::Struct(_)) => {
+                    error_at(location, &format!("redefinition of '{}'", tag.name))
+                }
+                Some(_) => wrong_kind(tag),
+                None => {
+                    let aggregate = Aggregate::new(Some(tag.name.clone()), is_union);
+                    lexer.declare_tag(tag, Type::Struct(aggregate.clone()));
+                    aggregate
+                }
+            },
+            None => Aggregate::new(None, is_union),
+        };
+        aggregate.define(struct_members(lexer));
+        Type::Struct(aggregate)
+    }
+
+    // 列挙型
+    // enum ident? { ident (= const_expr)? (, ident (= const_expr)?)* ,? }
+    // enum ident
+    // 列挙型はintとして扱い、タグは構造体、共用体と同じ名前空間を使う
+    fn enum_decl(lexer: &mut Lexer) -> Type {
+        let location = lexer.location();
+        let tag = match lexer.consume_ident() {
+            Ok(Token::Operator(OperatorKind::Ident(ident))) => Some(ident),
+            _ => None,
+        };
+        if lexer
+            .consume(Token::Operator(OperatorKind::LBrace))
+            .is_err()
+        {
+            let tag = match tag {
+                Some(tag) => tag,
+                None => error_at(location, "expected '{' after enum"),
+            };
+            return match lexer.find_tag(&tag) {
+                Some(Type::Struct(_)) => error_at(
+                    location,
+                    &format!("'{}' defined as wrong kind of tag", tag.name),
+                ),
+                Some(ty) => ty,
+                None => error_at(location, &format!("use of undeclared enum '{}'", tag.name)),
+            };
+        }
+
+        if let Some(tag) = &tag {
+            match lexer.find_local_tag(tag) {
+                Some(Type::Struct(_)) => error_at(
+                    location,
+                    &format!("'{}' defined as wrong kind of tag", tag.name),
+                ),
+                Some(_) => error_at(location, &format!("redefinition of '{}'", tag.name)),
+                None => lexer.declare_tag(tag, Type::Int),
+            }
+        }
+
+        // 値を省略した列挙子は直前の値に1を足した値になる
+        let mut value = 0;
+        loop {
+            let location = lexer.location();
+            let ident = get_ident(lexer);
+            if lexer.consume(Token::Operator(OperatorKind::Equal)).is_ok() {
+                value = const_expr(lexer);
+            }
+            if normalize(value, &Type::Int) != value {
+                error_at(location, "enumerator value is not representable in int");
+            }
+            if !lexer.declare_enum(&ident, value) {
+                error_at(location, &format!("redefinition of '{}'", ident.name));
+            }
+            value += 1;
+            let comma = lexer.consume(Token::Operator(OperatorKind::Comma)).is_ok();
+            if lexer.consume(Token::Operator(OperatorKind::RBrace)).is_ok() {
+                break;
+            }
+            if !comma {
+                eprintln!("expect ',' or '}}' but disappear");
+                exit(1);
+            }
+        }
+        Type::Int
+    }
+
+    // 構造体のメンバーの宣言
+    // (basetype declarator (, declarator)* ;)* }
+    fn struct_members(lexer: &mut Lexer) -> Vec<(String, Type)> {
+        let mut members: Vec<(String, Type)> = Vec::new();
+        while lexer
+            .consume(Token::Operator(OperatorKind::RBrace))
+            .is_err()
+        {
+            let basety = basetype(lexer);
+            loop {
+                let location = lexer.location();
+                let (ident, ty) = declarator(lexer, basety.clone());
+                if !ty.is_complete() {
+                    error_at(location, "field has incomplete type");
+                }
+                if members.iter().any(|(name, _)| *name == ident.name) {
+                    error_at(location, &format!("duplicate member '{}'", ident.name));
+                }
+                members.push((ident.name, ty));
+                if lexer.consume(Token::Operator(OperatorKind::Semi)).is_ok() {
+                    break;
+                }
+                if lexer.consume(Token::Operator(OperatorKind::Comma)).is_err() {
+                    eprintln!("expect ',' or ';' but disappear");
+                    exit(1);
+                }
+            }
+        }
+        members
+    }
+
+    // 宣言子
+    // "*"* ("(" declarator ")" | ident) type_suffix
+    fn declarator(lexer: &mut Lexer, ty: Type) -> (Ident, Type) {
+        match direct_declarator(lexer, ty) {
+            (Some(ident), ty) => (ident, ty),
+            (None, _) => {
+                eprintln!("expect identifier but disappear");
+                exit(1);
+            }
+        }
+    }
+
+    // 抽象宣言子
+    // 型名の中で使う名前のない宣言子
+    // "*"* ("(" abstract_declarator ")")? type_suffix
+    fn abstract_declarator(lexer: &mut Lexer, ty: Type) -> Type {
+        let location = lexer.location();
+        match direct_declarator(lexer, ty) {
+            (None, ty) => ty,
+            (Some(ident), _) => error_at(
+                location,
+                &format!("unexpected identifier '{}' in type name", ident.name),
+            ),
+        }
+    }
+
+    // 名前を省略できる宣言子
+    // 括弧の中の宣言子は外側の型を包むので、一度読み飛ばして外側の型を決めてから読み直す
+    // int (*a)[3] は「intの3要素の配列」へのポインタになる
+    fn direct_declarator(lexer: &mut Lexer, ty: Type) -> (Option<Ident>, Type) {
+        let ty = pointers(lexer, ty);
+        if !is_nested_declarator(lexer) {
+            let ident = match lexer.consume_ident() {
+                Ok(Token::Operator(OperatorKind::Ident(ident))) => Some(ident),
+                _ => None,
+            };
+            return (ident, type_suffix(lexer, ty));
+        }
+        let _ = lexer.consume(Token::Operator(OperatorKind::LParen));
+        let start = lexer.save();
+        direct_declarator(lexer, Type::Int);
+        expect_rparen(lexer);
+        let ty = type_suffix(lexer, ty);
+        let end = lexer.save();
+        lexer.restore(start);
+        let result = direct_declarator(lexer, ty);
+        lexer.restore(end);
+        result
+    }
+
+    // "*"*
+    // 先頭の*の数だけポインタ型になる
+    fn pointers(lexer: &mut Lexer, mut ty: Type) -> Type {
+        while lexer.consume(Token::Operator(OperatorKind::Mul)).is_ok() {
+            ty = Type::pointer_to(ty);
+        }
+        ty
+    }
+
+    // 宣言子の先頭の ( が入れ子の宣言子を囲むものかどうか
+    // 型名や ) が続くときは関数の引数の並びになる
+    fn is_nested_declarator(lexer: &mut Lexer) -> bool {
+        let position = lexer.save();
+        let nested = lexer.consume(Token::Operator(OperatorKind::LParen)).is_ok()
+            && (lexer.expect(Token::Operator(OperatorKind::Mul))
+                || lexer.expect(Token::Operator(OperatorKind::LParen))
+                || (!is_typename(lexer) && lexer.consume_ident().is_ok()));
+        lexer.restore(position);
+        nested
+    }
+
+    // ("[" const_expr "]")* | "(" func_params
+    // int a[2][3] は「intの3要素の配列」の2要素の配列になる
+    // int f(int) は「intを受け取りintを返す関数」になる
+    fn type_suffix(lexer: &mut Lexer, ty: Type) -> Type {
+        let location = lexer.location();
+        if lexer.consume(Token::Operator(OperatorKind::LParen)).is_ok() {
+            if ty.is_array() || ty.is_func() {
+                error_at(location, "function cannot return array or function type");
+            }
+            return Type::func(ty, func_params(lexer));
+        }
+        if lexer
+            .consume(Token::Operator(OperatorKind::LBracket))
+            .is_err()
+        {
+            return ty;
+        }
+        let location = lexer.location();
+        let len = const_expr(lexer);
+        if len < 0 {
+            error_at(location, "size of array is negative");
+        }
+        expect_rbracket(lexer);
+        let base = type_suffix(lexer, ty);
+        if base.is_func() {
+            error_at(location, "array of functions is not allowed");
+        }
+        Type::array_of(base, len as usize)
+    }
+
+    // 関数の型の引数の並び
+    // (void | basetype abstract_declarator (, basetype abstract_declarator)*)? )
+    // 引数の名前は書いてもよいが型には含めない
+    fn func_params(lexer: &mut Lexer) -> Vec<Type> {
+        let mut params = Vec::new();
+        if void_params(lexer) {
+            return params;
+        }
+        while lexer
+            .consume(Token::Operator(OperatorKind::RParen))
+            .is_err()
+        {
+            if !params.is_empty() && lexer.consume(Token::Operator(OperatorKind::Comma)).is_err() {
+                eprintln!("expect ',' but disappear");
+                exit(1);
+            }
+            let basety = basetype(lexer);
+            let (_, ty) = direct_declarator(lexer, basety);
+            params.push(param_type(ty));
+        }
+        params
+    }
+
+    // (void) は引数のない関数を表す
+    fn void_params(lexer: &mut Lexer) -> bool {
+        let position = lexer.save();
+        if lexer.consume(Token::Keyword(KeywordKind::Void)).is_ok()
+            && lexer.consume(Token::Operator(OperatorKind::RParen)).is_ok()
+        {
+            return true;
+        }
+        lexer.restore(position);
+        false
+    }
+
+    // 引数の型
+    // 配列と関数の引数はポインタとして受け取る
+    fn param_type(ty: Type) -> Type {
+        match ty {
+            Type::Array(_, _) | Type::Func(_, _) => ty.decay(),
+            ty => ty,
+        }
+    }
+
+    // 型名
+    // basetype abstract_declarator
+    fn type_name(lexer: &mut Lexer) -> Type {
+        let ty = basetype(lexer);
+        abstract_declarator(lexer, ty)
+    }
+
+    // ( type_name )
+    // 括弧の中が型名でなければ読み進めずNoneを返す
+    fn paren_type_name(lexer: &mut Lexer) -> Option<Type> {
+        let position = lexer.save();
+        if lexer
+            .consume(Token::Operator(OperatorKind::LParen))
+            .is_err()
+            || !is_typename(lexer)
+        {
+            lexer.restore(position);
+            return None;
+        }
+        let ty = type_name(lexer);
+        if lexer
+            .consume(Token::Operator(OperatorKind::RParen))
+            .is_err()
+        {
+            eprintln!("expect ')' but disappear");
+            exit(1);
+        }
+        Some(ty)
+    }
+
+    // 閉じられていないコメントがあれば、その開始位置を報告して終了する
+    // コメントの後ろは入力の終わりになるので、宣言や文の区切りで調べる
+    fn check_comment(lexer: &mut Lexer) {
+        let position = lexer.save();
+        if let Err(ErrorToken::UnterminatedComment(line, column)) = lexer.next_token() {
+            error_at((line, column), "unterminated comment");
+        }
+        lexer.restore(position);
+    }
+
+    // 位置付きでエラーを報告して終了する
+    fn error_at(location: (usize, usize), message: &str) -> ! {
+        eprintln!("{}:{}: {}", location.0, location.1, message);
+        exit(1);
+    }
+
+    // 定数式
+    // コンパイル時に値が決まらなければエラーにする
+    fn const_expr(lexer: &mut Lexer) -> i64 {
+        let location = lexer.location();
+        let tree = assign(lexer);
+        match eval(&tree) {
+            Ok(n) => n,
+            Err(e) => error_at(location, &e.to_string()),
+        }
+    }
+
+    fn expect_rparen(lexer: &mut Lexer) {
+        if lexer
+            .consume(Token::Operator(OperatorKind::RParen))
+            .is_err()
+        {
+            eprintln!("expect ')' but disappear");
+            exit(1);
+        }
+    }
+
+    fn expect_rbracket(lexer: &mut Lexer) {
+        if lexer
+            .consume(Token::Operator(OperatorKind::RBracket))
+            .is_err()
+        {
+            eprintln!("expect ']' but disappear");
+            exit(1);
+        }
+    }
+
+    // 現在のスコープに変数を作る
+    // 同じスコープで名前が使われていればエラーにする
+    fn declare_var(lexer: &mut Lexer, ident: Ident, ty: Type, location: (usize, usize)) -> Var {
+        let name = ident.name.clone();
+        match lexer.declare_var(ident, ty) {
+            Some(var) => var,
+            None => error_at(location, &format!("redefinition of '{}'", name)),
+        }
+    }
+
+    // 変数宣言
+    // basetype declarator (= assign)? (, declarator (= assign)?)* ;
+    // 初期化式は代入式の並びに変換する
+    fn declaration(lexer: &mut Lexer) -> Tree {
+        let mut trees = Vec::new();
+        if lexer.consume(Token::Keyword(KeywordKind::Typedef)).is_ok() {
+            typedef_decl(lexer);
+            return Tree::Block(trees);
+        }
+        let ty = basetype(lexer);
+        if lexer.consume(Token::Operator(OperatorKind::Semi)).is_ok() {
+            return Tree::Block(trees);
+        }
+        loop {
+            let location = lexer.location();
+            let (ident, ty) = declarator(lexer, ty.clone());
+            if !ty.is_complete() {
+                error_at(location, "variable has incomplete type");
+            }
+            let var = declare_var(lexer, ident, ty, location);
+            if lexer.consume(Token::Operator(OperatorKind::Equal)).is_ok() {
+                trees.push(new_assign(Tree::new_val(var), assign(lexer)));
+            }
+            if lexer.consume(Token::Operator(OperatorKind::Semi)).is_ok() {
+                break;
+            }
+            if lexer.consume(Token::Operator(OperatorKind::Comma)).is_err() {
+                eprintln!("expect ',' or ';' but disappear");
+                exit(1);
+            }
+        }
+        Tree::Block(trees)
+    }
+
+    // 命令
+    fn stmt(lexer: &mut Lexer, func: &mut FunctionState) -> Tree {
+        if lexer.consume(Token::Operator(OperatorKind::LBrace)).is_ok() {
+            return compound_stmt(lexer, func);
+        }
+        // 空文
+        if lexer.consume(Token::Operator(OperatorKind::Semi)).is_ok() {
+            return Tree::Block(vec![]);
+        }
+        if lexer.consume(Token::Keyword(KeywordKind::If)).is_ok() {
+            return if_stmt(lexer, func);
+        }
+        if lexer.consume(Token::Keyword(KeywordKind::While)).is_ok() {
+            let cond = scalar(paren_expr(lexer));
+            return Tree::new_while(cond, stmt(lexer, func));
+        }
+        if lexer.consume(Token::Keyword(KeywordKind::Do)).is_ok() {
+            return do_while_stmt(lexer, func);
+        }
+        if lexer.consume(Token::Keyword(KeywordKind::For)).is_ok() {
+            return for_stmt(lexer, func);
+        }
+        if lexer.consume(Token::Keyword(KeywordKind::Switch)).is_ok() {
+            return switch_stmt(lexer, func);
+        }
+        if lexer.expect(Token::Keyword(KeywordKind::Case)) {
+            return case_stmt(lexer, func);
+        }
+        if lexer.expect(Token::Keyword(KeywordKind::Default)) {
+            return default_stmt(lexer, func);
+        }
+        if let Some(tree) = labeled_stmt(lexer, func) {
+            return tree;
+        }
+
+        let tree = if lexer.consume(Token::Keyword(KeywordKind::Return)).is_ok() {
+            Tree::new_return(scalar(expr(lexer)))
+        } else if lexer.consume(Token::Keyword(KeywordKind::Break)).is_ok() {
+            Tree::Break
+        } else if lexer.consume(Token::Keyword(KeywordKind::Continue)).is_ok() {
+            Tree::Continue
+        } else if lexer.consume(Token::Keyword(KeywordKind::Goto)).is_ok() {
+            let location = lexer.location();
+            let ident = get_ident(lexer);
+            func.use_label(&ident.name, location);
+            Tree::Goto(ident.name)
+        } else {
+            expr(lexer)
+        };
+        expect_semi(lexer);
+        tree
+    }
+
+    fn expect_semi(lexer: &mut Lexer) {
+        if lexer.consume(Token::Operator(OperatorKind::Semi)).is_err() {
+            eprintln!("expected semi");
+            exit(1);
+        }
+    }
+
+    // { stmt* }
+    // ブロック内で作られた変数はブロックの外からは見えない
+    fn compound_stmt(lexer: &mut Lexer, func: &mut FunctionState) -> Tree {
+        let mut trees = Vec::new();
+        lexer.enter_scope();
+        while lexer
+            .consume(Token::Operator(OperatorKind::RBrace))
+            .is_err()
+        {
+            if lexer.expect(Token::EOF) {
+                eprintln!("expect '}}' but disappear");
+                exit(1);
+            }
+            check_comment(lexer);
+            if is_typename(lexer) {
+                trees.push(declaration(lexer));
+            } else {
+                trees.push(stmt(lexer, func));
+            }
+        }
+        lexer.leave_scope();
+        Tree::Block(trees)
+    }
+
+    // if (cond) stmt
+    // if (cond) stmt else stmt
+    fn if_stmt(lexer: &mut Lexer, func: &mut FunctionState) -> Tree {
+        let cond = scalar(paren_expr(lexer));
+        let then = stmt(lexer, func);
+        let els = if lexer.consume(Token::Keyword(KeywordKind::Else)).is_ok() {
+            Some(stmt(lexer, func))
+        } else {
+            None
+        };
+        Tree::new_if(cond, then, els)
+    }
+
+    // do stmt while (cond);
+    fn do_while_stmt(lexer: &mut Lexer, func: &mut FunctionState) -> Tree {
+        let body = stmt(lexer, func);
+        if lexer.consume(Token::Keyword(KeywordKind::While)).is_err() {
+            eprintln!("expect 'while' but disappear");
+            exit(1);
+        }
+        let cond = scalar(paren_expr(lexer));
+        expect_semi(lexer);
+        Tree::new_do_while(body, cond)
+    }
+
+    // ident: stmt
+    // 識別子の後ろが:でなければ式文なので読んだ位置を戻す
+    fn labeled_stmt(lexer: &mut Lexer, func: &mut FunctionState) -> Option<Tree> {
+        let position = lexer.save();
+        let location = lexer.location();
+        let ident = match lexer.consume_ident() {
+            Ok(Token::Operator(OperatorKind::Ident(ident))) => ident,
+            _ => return None,
+        };
+        if lexer.consume(Token::Operator(OperatorKind::Colon)).is_err() {
+            lexer.restore(position);
+            return None;
+        }
+        if !func.define_label(&ident.name) {
+            error_at(location, &format!("duplicate label '{}'", ident.name));
+        }
+        Some(Tree::Label(ident.name, Box::new(stmt(lexer, func))))
+    }
+
+    // switch (cond) stmt
+    // 条件式は整数拡張した型で比較する
+    fn switch_stmt(lexer: &mut Lexer, func: &mut FunctionState) -> Tree {
+        let location = lexer.location();
+        let cond = paren_expr(lexer);
+        if !cond.ty().is_integer() {
+            error_at(location, "switch quantity is not an integer");
+        }
+        func.enter_switch(cond.ty().promote());
+        let body = stmt(lexer, func);
+        let cases = func.leave_switch();
+        Tree::Switch(Box::new(cond), Box::new(body), cases)
+    }
+
+    // case const_expr: stmt
+    // 値は条件式の型に変換してから重複を調べる
+    fn case_stmt(lexer: &mut Lexer, func: &mut FunctionState) -> Tree {
+        let location = lexer.location();
+        lexer.consume(Token::Keyword(KeywordKind::Case)).unwrap();
+        let value = const_expr(lexer);
+        expect_colon(lexer);
+        let cases = match func.current_switch() {
+            Some(cases) => cases,
+            None => error_at(location, "case label not within a switch statement"),
+        };
+        let value = normalize(value, &cases.ty);
+        if cases.values.contains(&value) {
+            error_at(location, &format!("duplicate case value '{}'", value));
+        }
+        cases.values.push(value);
+        let index = cases.values.len() - 1;
+        Tree::Case(index, Box::new(stmt(lexer, func)))
+    }
+
+    // default: stmt
+    fn default_stmt(lexer: &mut Lexer, func: &mut FunctionState) -> Tree {
+        let location = lexer.location();
+        lexer.consume(Token::Keyword(KeywordKind::Default)).unwrap();
+        expect_colon(lexer);
+        let cases = match func.current_switch() {
+            Some(cases) => cases,
+            None => error_at(location, "'default' label not within a switch statement"),
+        };
+        if cases.has_default {
+            error_at(location, "multiple default labels in one switch");
+        }
+        cases.has_default = true;
+        Tree::Default(Box::new(stmt(lexer, func)))
+    }
+
+    fn expect_colon(lexer: &mut Lexer) {
+        if lexer.consume(Token::Operator(OperatorKind::Colon)).is_err() {
+            let location = lexer.location();
+            error_at(location, "expected ':'");
+        }
+    }
+
+    // for (init; cond; step) stmt
+    // init, cond, stepはそれぞれ省略できる
+    // initで宣言した変数はfor文の中だけで使える
+    fn for_stmt(lexer: &mut Lexer, func: &mut FunctionState) -> Tree {
+        if lexer
+            .consume(Token::Operator(OperatorKind::LParen))
+            .is_err()
+        {
+            eprintln!("expect '(' but disappear");
+            exit(1);
+        }
+        lexer.enter_scope();
+        let init = if is_typename(lexer) {
+            Some(declaration(lexer))
+        } else {
+            let init = optional_expr(lexer, OperatorKind::Semi);
+            expect_semi(lexer);
+            init
+        };
+        let cond = optional_expr(lexer, OperatorKind::Semi).map(scalar);
+        expect_semi(lexer);
+        let step = optional_expr(lexer, OperatorKind::RParen);
+        if lexer
+            .consume(Token::Operator(OperatorKind::RParen))
+            .is_err()
+        {
+            eprintln!("expect ')' but disappear");
+            exit(1);
+        }
+        let tree = Tree::new_for(init, cond, step, stmt(lexer, func));
+        lexer.leave_scope();
+        tree
+    }
+
+    // 次のトークンがendなら式は省略されている
+    fn optional_expr(lexer: &mut Lexer, end: OperatorKind) -> Option<Tree> {
+        if lexer.expect(Token::Operator(end)) {
+            None
+        } else {
+            Some(expr(lexer))
+        }
+    }
+
+    // ( expr )
+    fn paren_expr(lexer: &mut Lexer) -> Tree {
+        if lexer
+            .consume(Token::Operator(OperatorKind::LParen))
+            .is_err()
+        {
+            eprintln!("expect '(' but disappear");
+            exit(1);
+        }
+        let tree = expr(lexer);
+        if lexer
+            .consume(Token::Operator(OperatorKind::RParen))
+            .is_err()
+        {
+            eprintln!("expect ')' but disappear");
+            exit(1);
+        }
+        tree
+    }
+
+    // 式
+    // a, b
+    fn expr(lexer: &mut Lexer) -> Tree {
+        let mut tree = assign(lexer);
+        while lexer.consume(Token::Operator(OperatorKind::Comma)).is_ok() {
+            tree = Tree::new_tree(NodeKind::Comma, tree, assign(lexer));
+        }
+        tree
+    }
+
+    // 代入式
+    // =, +=, -=, *=, /=, %=, &=, |=, ^=, <<=, >>=
+    fn assign(lexer: &mut Lexer) -> Tree {
+        let tree = conditional(lexer);
+        if lexer.consume(Token::Operator(OperatorKind::Equal)).is_ok() {
+            return new_assign(tree, assign(lexer));
+        }
+        let ops = [
+            (OperatorKind::AddAssign, NodeKind::Add),
+            (OperatorKind::SubAssign, NodeKind::Sub),
+            (OperatorKind::MulAssign, NodeKind::Mul),
+            (OperatorKind::DivAssign, NodeKind::Div),
+            (OperatorKind::ModAssign, NodeKind::Mod),
+            (OperatorKind::AndAssign, NodeKind::BitAnd),
+            (OperatorKind::OrAssign, NodeKind::BitOr),
+            (OperatorKind::XorAssign, NodeKind::BitXor),
+            (OperatorKind::ShlAssign, NodeKind::Shl),
+            (OperatorKind::ShrAssign, NodeKind::Shr),
+        ];
+        for (op, kind) in ops {
+            if lexer.consume(Token::Operator(op)).is_ok() {
+                return new_assign_op(kind, tree, assign(lexer));
+            }
+        }
+        tree
+    }
+
+    // a = b
+    // 構造体は同じ型どうしでだけ代入できる
+    fn new_assign(lhs: Tree, rhs: Tree) -> Tree {
+        if lhs.ty().is_func() {
+            eprintln!("function type is not assignable");
+            exit(1);
+        }
+        if (lhs.ty().is_struct() || rhs.ty().is_struct()) && lhs.ty() != rhs.ty() {
+            eprintln!("incompatible types in assignment");
+            exit(1);
         }
-        (trees, lexer)
+        Tree::new_tree(NodeKind::Assign, lhs, rhs)
     }
 
-    // 命令
-    fn stmt(lexer: &mut Lexer) -> Tree {
-        let tree = expr(lexer);
-        if lexer.consume(Token::Operator(OperatorKind::Semi)).is_err() {
-            eprintln!("expected semi");
+    // 算術や比較、条件に使えるのは整数とポインタだけ
+    fn scalar(tree: Tree) -> Tree {
+        if tree.ty().is_struct() {
+            eprintln!("used struct or union type value where scalar is required");
             exit(1);
         }
         tree
     }
 
-    // 式
-    fn expr(lexer: &mut Lexer) -> Tree {
-        assign(lexer)
+    // 二項演算
+    fn new_binary(kind: NodeKind, lhs: Tree, rhs: Tree) -> Tree {
+        Tree::new_tree(kind, scalar(lhs), scalar(rhs))
     }
 
-    // 代入式
-    fn assign(lexer: &mut Lexer) -> Tree {
+    // 条件演算子
+    // cond ? expr : conditional
+    fn conditional(lexer: &mut Lexer) -> Tree {
+        let cond = logical_or(lexer);
+        if lexer
+            .consume(Token::Operator(OperatorKind::Question))
+            .is_err()
+        {
+            return cond;
+        }
+        let then = expr(lexer);
+        expect_colon(lexer);
+        let els = conditional(lexer);
+        new_cond(scalar(cond), then, els)
+    }
+
+    // 算術型同士は通常の算術変換、ポインタ同士は指す先の型が同じときだけ許す
+    // ポインタと整数の組み合わせは整数が0のときだけ許す
+    fn new_cond(cond: Tree, then: Tree, els: Tree) -> Tree {
+        let is_null = |tree: &Tree| matches!(tree, Tree::Num(0, _));
+        let (then_ty, els_ty) = (then.ty().decay(), els.ty().decay());
+        let ty = match (then_ty.base(), els_ty.base()) {
+            // 構造体は両辺が同じ型のときだけ選べる
+            _ if then.ty().is_struct() || els.ty().is_struct() => {
+                if then.ty() != els.ty() {
+                    eprintln!("type mismatch in conditional expression");
+                    exit(1);
+                }
+                then.ty().clone()
+            }
+            (None, None) => Type::common_type(then.ty(), els.ty()),
+            (Some(lhs), Some(rhs)) if lhs == rhs => Type::pointer_to(lhs.clone()),
+            (Some(base), None) if is_null(&els) => Type::pointer_to(base.clone()),
+            (None, Some(base)) if is_null(&then) => Type::pointer_to(base.clone()),
+            _ => {
+                eprintln!("type mismatch in conditional expression");
+                exit(1);
+            }
+        };
+        Tree::Cond(Box::new(cond), Box::new(then), Box::new(els), ty)
+    }
+
+    // a op= b
+    // ポインタへの加減算は右辺を指す先の型の大きさ倍する
+    fn new_assign_op(kind: NodeKind, lhs: Tree, rhs: Tree) -> Tree {
+        let (lhs, rhs) = (scalar(lhs), scalar(rhs));
+        let ty = lhs.ty().clone();
+        if ty.is_array() {
+            eprintln!("array type is not assignable");
+            exit(1);
+        }
+        let rhs = match (ty.base(), &kind) {
+            (None, _) => rhs,
+            (Some(base), NodeKind::Add | NodeKind::Sub) if rhs.ty().base().is_none() => {
+                scale(rhs, base.size())
+            }
+            _ => {
+                eprintln!("invalid operands to compound assignment");
+                exit(1);
+            }
+        };
+        Tree::AssignOp(kind, Box::new(lhs), Box::new(rhs), ty)
+    }
+
+    // ++, --で増減する量
+    fn step_of(tree: &Tree) -> usize {
+        tree.ty().base().map_or(1, |base| base.size())
+    }
+
+    // 論理和 ||
+    fn logical_or(lexer: &mut Lexer) -> Tree {
+        let mut tree = logical_and(lexer);
+        while lexer
+            .consume(Token::Operator(OperatorKind::LogicalOr))
+            .is_ok()
+        {
+            tree = new_binary(NodeKind::LogicalOr, tree, logical_and(lexer));
+        }
+        tree
+    }
+
+    // 論理積 &&
+    fn logical_and(lexer: &mut Lexer) -> Tree {
+        let mut tree = bit_or(lexer);
+        while lexer
+            .consume(Token::Operator(OperatorKind::LogicalAnd))
+            .is_ok()
+        {
+            tree = new_binary(NodeKind::LogicalAnd, tree, bit_or(lexer));
+        }
+        tree
+    }
+
+    // ビット和 |
+    fn bit_or(lexer: &mut Lexer) -> Tree {
+        let mut tree = bit_xor(lexer);
+        while lexer.consume(Token::Operator(OperatorKind::Pipe)).is_ok() {
+            tree = new_binary(NodeKind::BitOr, tree, bit_xor(lexer));
+        }
+        tree
+    }
+
+    // 排他的論理和 ^
+    fn bit_xor(lexer: &mut Lexer) -> Tree {
+        let mut tree = bit_and(lexer);
+        while lexer.consume(Token::Operator(OperatorKind::Caret)).is_ok() {
+            tree = new_binary(NodeKind::BitXor, tree, bit_and(lexer));
+        }
+        tree
+    }
+
+    // ビット積 &
+    fn bit_and(lexer: &mut Lexer) -> Tree {
         let mut tree = equality(lexer);
-        if lexer.consume(Token::Operator(OperatorKind::Equal)).is_ok() {
-            tree = Tree::new_tree(NodeKind::Assign, tree, assign(lexer));
+        while lexer
+            .consume(Token::Operator(OperatorKind::Ampersand))
+            .is_ok()
+        {
+            tree = new_binary(NodeKind::BitAnd, tree, equality(lexer));
         }
         tree
     }
@@ -48,13 +1208,13 @@ pub mod myparser {
                 .consume(Token::Operator(OperatorKind::Equality))
                 .is_ok()
             {
-                tree = Tree::new_tree(NodeKind::Equality, tree, relational(lexer));
+                tree = new_binary(NodeKind::Equality, tree, relational(lexer));
             }
             if lexer
                 .consume(Token::Operator(OperatorKind::Nonequality))
                 .is_ok()
             {
-                tree = Tree::new_tree(NodeKind::Nonequality, tree, relational(lexer));
+                tree = new_binary(NodeKind::Nonequality, tree, relational(lexer));
             }
         }
         tree
@@ -62,7 +1222,7 @@ pub mod myparser {
 
     // 比較 <, <=, >, >=
     fn relational(lexer: &mut Lexer) -> Tree {
-        let mut tree = add(lexer);
+        let mut tree = shift(lexer);
         while lexer.expect(Token::Operator(OperatorKind::Less))
             || lexer.expect(Token::Operator(OperatorKind::LessOrEqual))
             || lexer.expect(Token::Operator(OperatorKind::Greater))
@@ -72,24 +1232,38 @@ pub mod myparser {
                 .consume(Token::Operator(OperatorKind::LessOrEqual))
                 .is_ok()
             {
-                tree = Tree::new_tree(NodeKind::LessOrEqual, tree, add(lexer));
+                tree = new_binary(NodeKind::LessOrEqual, tree, shift(lexer));
             } else if lexer.consume(Token::Operator(OperatorKind::Less)).is_ok() {
-                tree = Tree::new_tree(NodeKind::Less, tree, add(lexer));
+                tree = new_binary(NodeKind::Less, tree, shift(lexer));
             } else if lexer
                 .consume(Token::Operator(OperatorKind::GreaterOrEqual))
                 .is_ok()
             {
-                tree = Tree::new_tree(NodeKind::LessOrEqual, add(lexer), tree);
+                tree = new_binary(NodeKind::LessOrEqual, shift(lexer), tree);
             } else if lexer
                 .consume(Token::Operator(OperatorKind::Greater))
                 .is_ok()
             {
-                tree = Tree::new_tree(NodeKind::Less, add(lexer), tree);
+                tree = new_binary(NodeKind::Less, shift(lexer), tree);
             }
         }
         tree
     }
 
+    // シフト <<, >>
+    fn shift(lexer: &mut Lexer) -> Tree {
+        let mut tree = add(lexer);
+        loop {
+            if lexer.consume(Token::Operator(OperatorKind::Shl)).is_ok() {
+                tree = new_binary(NodeKind::Shl, tree, add(lexer));
+            } else if lexer.consume(Token::Operator(OperatorKind::Shr)).is_ok() {
+                tree = new_binary(NodeKind::Shr, tree, add(lexer));
+            } else {
+                return tree;
+            }
+        }
+    }
+
     // 加減算 +, -
     fn add(lexer: &mut Lexer) -> Tree {
         let mut tree = mul(lexer);
@@ -97,26 +1271,89 @@ pub mod myparser {
             || lexer.expect(Token::Operator(OperatorKind::Sub))
         {
             if lexer.consume(Token::Operator(OperatorKind::Add)).is_ok() {
-                tree = Tree::new_tree(NodeKind::Add, tree, mul(lexer));
+                tree = new_add(tree, mul(lexer));
             }
             if lexer.consume(Token::Operator(OperatorKind::Sub)).is_ok() {
-                tree = Tree::new_tree(NodeKind::Sub, tree, mul(lexer));
+                tree = new_sub(tree, mul(lexer));
             }
         }
         tree
     }
 
-    // 乗除算 *, /
+    // ポインタに足す整数は指す先の型の大きさ倍する
+    fn scale(tree: Tree, size: usize) -> Tree {
+        Tree::Node(
+            NodeKind::Mul,
+            Box::new(tree),
+            Box::new(Tree::Num(size, Type::Long)),
+            Type::Long,
+        )
+    }
+
+    // ptr + int, int + ptr はポインタになる
+    // 配列は先頭要素へのポインタとして扱う
+    fn new_add(lhs: Tree, rhs: Tree) -> Tree {
+        let (lhs, rhs) = (scalar(lhs), scalar(rhs));
+        match (lhs.ty().base(), rhs.ty().base()) {
+            (None, None) => Tree::new_tree(NodeKind::Add, lhs, rhs),
+            (Some(base), None) => {
+                let ty = Type::pointer_to(base.clone());
+                let rhs = scale(rhs, base.size());
+                Tree::Node(NodeKind::Add, Box::new(lhs), Box::new(rhs), ty)
+            }
+            (None, Some(_)) => new_add(rhs, lhs),
+            (Some(_), Some(_)) => {
+                eprintln!("invalid operands to binary +");
+                exit(1);
+            }
+        }
+    }
+
+    // ptr - int はポインタに、ptr - ptr は間の要素数になる
+    fn new_sub(lhs: Tree, rhs: Tree) -> Tree {
+        let (lhs, rhs) = (scalar(lhs), scalar(rhs));
+        match (lhs.ty().base(), rhs.ty().base()) {
+            (None, None) => Tree::new_tree(NodeKind::Sub, lhs, rhs),
+            (Some(base), None) => {
+                let ty = Type::pointer_to(base.clone());
+                let rhs = scale(rhs, base.size());
+                Tree::Node(NodeKind::Sub, Box::new(lhs), Box::new(rhs), ty)
+            }
+            (Some(base), Some(_)) => {
+                let size = base.size();
+                let diff = Tree::Node(NodeKind::Sub, Box::new(lhs), Box::new(rhs), Type::Long);
+                Tree::Node(
+                    NodeKind::Div,
+                    Box::new(diff),
+                    Box::new(Tree::Num(size, Type::Long)),
+                    Type::Long,
+                )
+            }
+            (None, Some(_)) => {
+                eprintln!("invalid operands to binary -");
+                exit(1);
+            }
+        }
+    }
+
+    // 乗除算 *, /, %
     fn mul(lexer: &mut Lexer) -> Tree {
         let mut tree = unary(lexer);
         while lexer.expect(Token::Operator(OperatorKind::Mul))
             || lexer.expect(Token::Operator(OperatorKind::Div))
+            || lexer.expect(Token::Operator(OperatorKind::Percent))
         {
             if lexer.consume(Token::Operator(OperatorKind::Mul)).is_ok() {
-                tree = Tree::new_tree(NodeKind::Mul, tree, unary(lexer));
+                tree = new_binary(NodeKind::Mul, tree, unary(lexer));
             }
             if lexer.consume(Token::Operator(OperatorKind::Div)).is_ok() {
-                tree = Tree::new_tree(NodeKind::Div, tree, unary(lexer));
+                tree = new_binary(NodeKind::Div, tree, unary(lexer));
+            }
+            if lexer
+                .consume(Token::Operator(OperatorKind::Percent))
+                .is_ok()
+            {
+                tree = new_binary(NodeKind::Mod, tree, unary(lexer));
             }
         }
         tree
@@ -125,16 +1362,211 @@ pub mod myparser {
     // 単行演算子 +, -
     fn unary(lexer: &mut Lexer) -> Tree {
         if lexer.consume(Token::Operator(OperatorKind::Add)).is_ok() {
-            return primary(lexer);
+            return unary(lexer);
         }
         if lexer.consume(Token::Operator(OperatorKind::Sub)).is_ok() {
-            return Tree::new_tree(NodeKind::Sub, Tree::Num(0), primary(lexer));
+            return new_binary(NodeKind::Sub, Tree::new_num(0), unary(lexer));
+        }
+        // ++a は a += 1 と同じ
+        if lexer.consume(Token::Operator(OperatorKind::Inc)).is_ok() {
+            return new_assign_op(NodeKind::Add, unary(lexer), Tree::new_num(1));
+        }
+        if lexer.consume(Token::Operator(OperatorKind::Dec)).is_ok() {
+            return new_assign_op(NodeKind::Sub, unary(lexer), Tree::new_num(1));
+        }
+        if lexer.consume(Token::Operator(OperatorKind::Not)).is_ok() {
+            return Tree::new_unary(NodeKind::Not, scalar(unary(lexer)), Type::Int);
+        }
+        if lexer.consume(Token::Operator(OperatorKind::Tilde)).is_ok() {
+            let tree = scalar(unary(lexer));
+            let ty = tree.ty().promote();
+            return Tree::new_unary(NodeKind::BitNot, tree, ty);
+        }
+        if lexer
+            .consume(Token::Operator(OperatorKind::Ampersand))
+            .is_ok()
+        {
+            let tree = unary(lexer);
+            let ty = Type::pointer_to(tree.ty().clone());
+            return Tree::new_unary(NodeKind::Addr, tree, ty);
+        }
+        if lexer.consume(Token::Operator(OperatorKind::Mul)).is_ok() {
+            let tree = unary(lexer);
+            // 関数を参照外ししても同じ関数になる
+            if tree.ty().is_func() {
+                return tree;
+            }
+            let ty = match tree.ty().base() {
+                Some(base) => base.clone(),
+                None => {
+                    eprintln!("invalid pointer dereference");
+                    exit(1);
+                }
+            };
+            return Tree::new_unary(NodeKind::Deref, tree, ty);
+        }
+        // sizeof, _Alignofは型の大きさの定数になる
+        // 型はsize_tにあたるunsigned long
+        if lexer.consume(Token::Keyword(KeywordKind::Sizeof)).is_ok() {
+            let location = lexer.location();
+            let ty = match paren_type_name(lexer) {
+                Some(ty) => ty,
+                None => unary(lexer).ty().clone(),
+            };
+            if !ty.is_complete() {
+                error_at(
+                    location,
+                    "invalid application of 'sizeof' to an incomplete type",
+                );
+            }
+            return Tree::Num(ty.size(), Type::ULong);
+        }
+        if lexer.consume(Token::Keyword(KeywordKind::Alignof)).is_ok() {
+            let location = lexer.location();
+            match paren_type_name(lexer) {
+                Some(ty) => return Tree::Num(ty.align(), Type::ULong),
+                None => error_at(location, "expect '(' type name ')' after _Alignof"),
+            }
+        }
+        let location = lexer.location();
+        if let Some(ty) = paren_type_name(lexer) {
+            return new_cast(location, unary(lexer), ty);
+        }
+        postfix(lexer)
+    }
+
+    // 型変換 (type_name) unary
+    // 整数とポインタの間でだけ変換できる
+    fn new_cast(location: (usize, usize), tree: Tree, ty: Type) -> Tree {
+        if ty.is_struct() || ty.is_array() || ty.is_func() {
+            error_at(location, "cast to non-scalar type");
+        }
+        if tree.ty().is_struct() {
+            error_at(location, "operand of cast has non-scalar type");
+        }
+        Tree::Cast(Box::new(tree), ty)
+    }
+
+    // 添字、メンバー、関数ポインタの呼び出し、後置の++, --
+    // a[i] は *(a + i) に、p->m は (*p).m に変換する
+    // a++ は (a += 1) - 1 として、増やす前の値を返す
+    fn postfix(lexer: &mut Lexer) -> Tree {
+        let mut tree = primary(lexer);
+        loop {
+            if lexer.consume(Token::Operator(OperatorKind::LParen)).is_ok() {
+                tree = indirect_call(lexer, tree);
+                continue;
+            }
+            if lexer.consume(Token::Operator(OperatorKind::Dot)).is_ok() {
+                tree = struct_ref(lexer, tree);
+                continue;
+            }
+            if lexer.consume(Token::Operator(OperatorKind::Arrow)).is_ok() {
+                let ty = match tree.ty() {
+                    Type::Ptr(base) => *base.clone(),
+                    _ => error_at(lexer.location(), "member reference type is not a pointer"),
+                };
+                tree = struct_ref(lexer, Tree::new_unary(NodeKind::Deref, tree, ty));
+                continue;
+            }
+            if lexer.consume(Token::Operator(OperatorKind::Inc)).is_ok() {
+                tree = new_post_inc(NodeKind::Add, NodeKind::Sub, tree);
+                continue;
+            }
+            if lexer.consume(Token::Operator(OperatorKind::Dec)).is_ok() {
+                tree = new_post_inc(NodeKind::Sub, NodeKind::Add, tree);
+                continue;
+            }
+            if lexer
+                .consume(Token::Operator(OperatorKind::LBracket))
+                .is_err()
+            {
+                return tree;
+            }
+            let index = expr(lexer);
+            expect_rbracket(lexer);
+            let sum = new_add(tree, index);
+            let ty = sum.ty().base().cloned().unwrap_or_else(|| {
+                eprintln!("subscripted value is not an array or pointer");
+                exit(1);
+            });
+            tree = Tree::new_unary(NodeKind::Deref, sum, ty);
+        }
+    }
+
+    // s.m
+    fn struct_ref(lexer: &mut Lexer, tree: Tree) -> Tree {
+        let location = lexer.location();
+        let ident = get_ident(lexer);
+        let member = match tree.ty() {
+            Type::Struct(aggregate) => aggregate.member(&ident.name),
+            _ => error_at(
+                location,
+                "member reference base type is not a structure or union",
+            ),
+        };
+        match member {
+            Some(member) => Tree::Member(Box::new(tree), member),
+            None => error_at(location, &format!("no member named '{}'", ident.name)),
+        }
+    }
+
+    // 増減した後の値から増減分を戻して元の値を求める
+    // 結果は元の変数の型に切り詰められる
+    fn new_post_inc(kind: NodeKind, undo: NodeKind, tree: Tree) -> Tree {
+        let ty = tree.ty().clone();
+        let step = Tree::Num(step_of(&tree), Type::Long);
+        let assign = new_assign_op(kind, tree, Tree::new_num(1));
+        Tree::Node(undo, Box::new(assign), Box::new(step), ty)
+    }
+
+    // 関数呼び出し
+    // name(arg, ...)
+    fn call(lexer: &mut Lexer, ident: Ident) -> Tree {
+        let args = call_args(lexer);
+        let ty = lexer.find_function(&ident);
+        Tree::new_call(ident.name, args, ty)
+    }
+
+    // 関数ポインタを通した呼び出し
+    // fp(arg, ...), (*fp)(arg, ...)
+    fn indirect_call(lexer: &mut Lexer, callee: Tree) -> Tree {
+        let ty = match callee.ty().return_type() {
+            Some(ty) => ty.clone(),
+            None => {
+                eprintln!("called object is not a function or function pointer");
+                exit(1);
+            }
+        };
+        let args = call_args(lexer);
+        Tree::IndirectCall(Box::new(callee), args, ty)
+    }
+
+    // 実引数の並び
+    // (assign (, assign)*)? )
+    fn call_args(lexer: &mut Lexer) -> Vec<Tree> {
+        let mut args = Vec::new();
+        while lexer
+            .consume(Token::Operator(OperatorKind::RParen))
+            .is_err()
+        {
+            if !args.is_empty() && lexer.consume(Token::Operator(OperatorKind::Comma)).is_err() {
+                eprintln!("expect ',' but disappear");
+                exit(1);
+            }
+            let arg = assign(lexer);
+            if arg.ty().is_struct() {
+                eprintln!("passing struct by value is not supported");
+                exit(1);
+            }
+            args.push(arg);
         }
-        primary(lexer)
+        args
     }
 
     // 数字
     fn primary(lexer: &mut Lexer) -> Tree {
+        let location = lexer.location();
         match lexer.next_token() {
             Ok(Token::Operator(OperatorKind::LParen)) => {
                 let tree = expr(lexer);
@@ -146,8 +1578,62 @@ pub mod myparser {
                     }
                 }
             }
-            Ok(Token::Operator(OperatorKind::Operand(n))) => Tree::new_num(n),
-            Ok(Token::Operator(OperatorKind::Ident(i))) => Tree::new_val(i, lexer),
+            Ok(Token::Operator(OperatorKind::Operand(n, ty))) => Tree::Num(n, ty),
+            // 文字定数はcharを符号拡張したint
+            Ok(Token::Operator(OperatorKind::CharLiteral(c))) => {
+                Tree::Num(c as i8 as NumType, Type::Int)
+            }
+            // 文字列リテラルは終端の\0を含むcharの配列
+            Ok(Token::Operator(OperatorKind::StringLiteral(mut bytes))) => {
+                bytes.push(0);
+                let ty = Type::array_of(Type::Char, bytes.len());
+                Tree::Str(lexer.add_string(bytes), ty)
+            }
+            Err(ErrorToken::UnterminatedComment(line, column)) => {
+                error_at((line, column), "unterminated comment")
+            }
+            Err(ErrorToken::UnterminatedLiteral(line, column)) => {
+                error_at((line, column), "unterminated literal")
+            }
+            Err(ErrorToken::EmptyCharLiteral(line, column)) => {
+                error_at((line, column), "empty character constant")
+            }
+            Err(ErrorToken::MultiCharLiteral(line, column)) => {
+                error_at((line, column), "multi-character character constant")
+            }
+            Err(ErrorToken::InvalidNumber(line, column)) => {
+                error_at((line, column), "invalid integer literal")
+            }
+            Err(ErrorToken::NumberOverflow(line, column)) => {
+                error_at((line, column), "integer literal is too large")
+            }
+            Err(ErrorToken::InvalidEscape(c, line, column)) => error_at(
+                (line, column),
+                &format!("invalid escape sequence '\\{}'", c),
+            ),
+            Ok(Token::Operator(OperatorKind::Ident(i))) => {
+                if let Some(value) = lexer.find_enum(&i) {
+                    return Tree::Num(value as NumType, Type::Int);
+                }
+                if lexer.find_typedef(&i).is_some() {
+                    error_at(location, &format!("unexpected type name '{}'", i.name));
+                }
+                // 関数ポインタの変数の呼び出しはpostfixで扱う
+                if let Some(var) = lexer.find_var(&i) {
+                    return Tree::new_val(var);
+                }
+                if lexer.consume(Token::Operator(OperatorKind::LParen)).is_ok() {
+                    return call(lexer, i);
+                }
+                // 関数名は関数の型を持ち、アドレスが値になる
+                match lexer.find_function_var(&i) {
+                    Some(var) => Tree::new_val(var),
+                    None => {
+                        eprintln!("undefined variable: {}", i.name);
+                        exit(1);
+                    }
+                }
+            }
             _ => {
                 eprintln!("expect number or block but disappear");
                 exit(1);
@@ -158,98 +1644,387 @@ pub mod myparser {
 
 #[cfg(test)]
 mod test {
-    use crate::{lexer::mylexer::Lexer, parser::myparser::*, tree::mytree::*};
+    use crate::{
+        lexer::mylexer::Lexer,
+        parser::myparser::*,
+        symbol::mysymbol::{Storage, Var},
+        tree::mytree::*,
+        types::mytype::{Member, Type},
+    };
+
+    // int main() { ... } の本体の文を取り出す
+    fn parse_body(input: &str) -> Vec<Tree> {
+        let lexer = &mut Lexer::new(&format!("int main() {{ {} }}", input));
+        let mut functions = program(lexer).functions;
+        match functions.remove(0).body {
+            Tree::Block(trees) => trees,
+            _ => panic!("function body is not a block"),
+        }
+    }
+
+    fn num(n: usize) -> Box<Tree> {
+        Box::new(Tree::Num(n, Type::Int))
+    }
+
+    fn node(kind: NodeKind, lhs: Box<Tree>, rhs: Box<Tree>) -> Box<Tree> {
+        Box::new(Tree::Node(kind, lhs, rhs, Type::Int))
+    }
+
+    fn var(name: &str, ty: Type, offset: usize) -> Var {
+        Var {
+            name: name.to_string(),
+            ty,
+            offset,
+            storage: Storage::Local,
+        }
+    }
 
     #[test]
     fn test_parser() {
-        let lexer1 = &mut Lexer::new("1+1;");
-        let (lexer1, _) = program(lexer1);
-        assert_eq!(
-            lexer1,
-            [Tree::Node(
-                NodeKind::Add,
-                Box::new(Tree::Num(1)),
-                Box::new(Tree::Num(1))
-            )]
-        );
+        let lexer1 = parse_body("1+1;");
+        assert_eq!(lexer1, [*node(NodeKind::Add, num(1), num(1))]);
 
-        let lexer2 = &mut Lexer::new("1+1*2;");
-        let (lexer2, _) = program(lexer2);
+        let lexer2 = parse_body("1+1*2;");
         assert_eq!(
             lexer2,
-            [Tree::Node(
+            [*node(
                 NodeKind::Add,
-                Box::new(Tree::Num(1)),
-                Box::new(Tree::Node(
-                    NodeKind::Mul,
-                    Box::new(Tree::Num(1)),
-                    Box::new(Tree::Num(2))
-                ))
+                num(1),
+                node(NodeKind::Mul, num(1), num(2))
             )]
         );
 
-        let lexer3 = &mut Lexer::new("3 * (2 + 3) - (6 / 2 + 2);");
-        let (lexer3, _) = program(lexer3);
+        let lexer3 = parse_body("3 * (2 + 3) - (6 / 2 + 2);");
         assert_eq!(
             lexer3,
-            [Tree::Node(
+            [*node(
                 NodeKind::Sub,
-                Box::new(Tree::Node(
-                    NodeKind::Mul,
-                    Box::new(Tree::Num(3)),
-                    Box::new(Tree::Node(
-                        NodeKind::Add,
-                        Box::new(Tree::Num(2)),
-                        Box::new(Tree::Num(3))
-                    ))
-                )),
-                Box::new(Tree::Node(
-                    NodeKind::Add,
-                    Box::new(Tree::Node(
-                        NodeKind::Div,
-                        Box::new(Tree::Num(6)),
-                        Box::new(Tree::Num(2))
-                    )),
-                    Box::new(Tree::Num(2))
-                ))
+                node(NodeKind::Mul, num(3), node(NodeKind::Add, num(2), num(3))),
+                node(NodeKind::Add, node(NodeKind::Div, num(6), num(2)), num(2))
             )]
         );
 
-        let lexer4 = &mut Lexer::new("5 + 6 * 7;");
-        let (lexer4, _) = program(lexer4);
+        let lexer4 = parse_body("5 + 6 * 7;");
         assert_eq!(
             lexer4,
-            [Tree::Node(
+            [*node(
                 NodeKind::Add,
-                Box::new(Tree::Num(5)),
-                Box::new(Tree::Node(
-                    NodeKind::Mul,
-                    Box::new(Tree::Num(6)),
-                    Box::new(Tree::Num(7))
-                ))
+                num(5),
+                node(NodeKind::Mul, num(6), num(7))
             )]
         );
 
-        let lexer5 = &mut Lexer::new("2 * 3 == 3 + 1;");
-        let (lexer5, _) = program(lexer5);
+        let lexer5 = parse_body("2 * 3 == 3 + 1;");
         assert_eq!(
             lexer5,
-            [Tree::Node(
+            [*node(
                 NodeKind::Equality,
+                node(NodeKind::Mul, num(2), num(3)),
+                node(NodeKind::Add, num(3), num(1))
+            )]
+        );
+
+        let lexer6 = parse_body("int a; a;");
+        assert_eq!(
+            lexer6,
+            [Tree::Block(vec![]), Tree::Val(var("a", Type::Int, 4))]
+        );
+    }
+
+    #[test]
+    fn test_if_stmt() {
+        let trees = parse_body("if (1) 2; else if (3) 4;");
+        assert_eq!(
+            trees,
+            [Tree::If(
+                num(1),
+                num(2),
+                Some(Box::new(Tree::If(num(3), num(4), None)))
+            )]
+        );
+    }
+
+    #[test]
+    fn test_function() {
+        let lexer = &mut Lexer::new(
+            "int add(int a, int b) { return a + b; } int main() { long x = 1; { char y = 2; } }",
+        );
+        let functions = program(lexer).functions;
+        let a = var("a", Type::Int, 4);
+        let b = var("b", Type::Int, 8);
+        let x = var("x", Type::Long, 8);
+        let y = var("y", Type::Char, 9);
+        assert_eq!(
+            functions,
+            [
+                Function {
+                    name: "add".to_string(),
+                    ty: Type::Int,
+                    params: vec![a.clone(), b.clone()],
+                    body: Tree::Block(vec![Tree::Return(node(
+                        NodeKind::Add,
+                        Box::new(Tree::Val(a)),
+                        Box::new(Tree::Val(b))
+                    ))]),
+                    stack_size: 8,
+                },
+                Function {
+                    name: "main".to_string(),
+                    ty: Type::Int,
+                    params: vec![],
+                    body: Tree::Block(vec![
+                        Tree::Block(vec![Tree::Node(
+                            NodeKind::Assign,
+                            Box::new(Tree::Val(x)),
+                            num(1),
+                            Type::Long
+                        )]),
+                        Tree::Block(vec![Tree::Block(vec![Tree::Node(
+                            NodeKind::Assign,
+                            Box::new(Tree::Val(y)),
+                            num(2),
+                            Type::Char
+                        )])]),
+                    ]),
+                    stack_size: 9,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pointer() {
+        let trees = parse_body("int *p; p + 2;");
+        let ptr = Type::pointer_to(Type::Int);
+        assert_eq!(
+            trees[1],
+            Tree::Node(
+                NodeKind::Add,
+                Box::new(Tree::Val(var("p", ptr.clone(), 8))),
                 Box::new(Tree::Node(
                     NodeKind::Mul,
-                    Box::new(Tree::Num(2)),
-                    Box::new(Tree::Num(3)),
+                    num(2),
+                    Box::new(Tree::Num(4, Type::Long)),
+                    Type::Long
                 )),
-                Box::new(Tree::Node(
-                    NodeKind::Add,
-                    Box::new(Tree::Num(3)),
-                    Box::new(Tree::Num(1)),
-                ))
+                ptr
+            )
+        );
+    }
+
+    #[test]
+    fn test_global() {
+        let lexer = &mut Lexer::new("int x = 3, *p; char c[4]; int main() { return x; }");
+        let program = program(lexer);
+        let global = |name: &str, ty: Type| Var {
+            name: name.to_string(),
+            ty,
+            offset: 0,
+            storage: Storage::Global,
+        };
+        assert_eq!(
+            program.globals,
+            [
+                GlobalVar {
+                    var: global("x", Type::Int),
+                    init: Some(3),
+                },
+                GlobalVar {
+                    var: global("p", Type::pointer_to(Type::Int)),
+                    init: None,
+                },
+                GlobalVar {
+                    var: global("c", Type::array_of(Type::Char, 4)),
+                    init: None,
+                },
+            ]
+        );
+        assert_eq!(
+            program.functions[0].body,
+            Tree::Block(vec![Tree::Return(Box::new(Tree::Val(global(
+                "x",
+                Type::Int
+            ))))])
+        );
+    }
+
+    #[test]
+    fn test_logical() {
+        let trees = parse_body("1 || 2 && !3;");
+        assert_eq!(
+            trees,
+            [*node(
+                NodeKind::LogicalOr,
+                num(1),
+                node(
+                    NodeKind::LogicalAnd,
+                    num(2),
+                    Box::new(Tree::Unary(NodeKind::Not, num(3), Type::Int))
+                )
+            )]
+        );
+    }
+
+    #[test]
+    fn test_call() {
+        let trees = parse_body("f(1, g(), 2 + 3);");
+        assert_eq!(
+            trees,
+            [Tree::Call(
+                "f".to_string(),
+                vec![
+                    *num(1),
+                    Tree::Call("g".to_string(), vec![], Type::Int),
+                    *node(NodeKind::Add, num(2), num(3)),
+                ],
+                Type::Int
             )]
         );
-        let lexer6 = &mut Lexer::new("a;");
-        let (lexer6, _) = program(lexer6);
-        assert_eq!(lexer6, [Tree::Val(8)]);
+    }
+
+    #[test]
+    fn test_assign_op() {
+        let trees = parse_body("int *p; p += 2; p++;");
+        let ptr = Type::pointer_to(Type::Int);
+        let p = || Box::new(Tree::Val(var("p", ptr.clone(), 8)));
+        let step = || Box::new(Tree::Num(4, Type::Long));
+        assert_eq!(
+            trees[1],
+            Tree::AssignOp(
+                NodeKind::Add,
+                p(),
+                Box::new(Tree::Node(NodeKind::Mul, num(2), step(), Type::Long)),
+                ptr.clone()
+            )
+        );
+        assert_eq!(
+            trees[2],
+            Tree::Node(
+                NodeKind::Sub,
+                Box::new(Tree::AssignOp(
+                    NodeKind::Add,
+                    p(),
+                    Box::new(Tree::Node(NodeKind::Mul, num(1), step(), Type::Long)),
+                    ptr.clone()
+                )),
+                step(),
+                ptr
+            )
+        );
+    }
+
+    #[test]
+    fn test_cond() {
+        let trees = parse_body("1 ? 2 : 3 ? 4 : 5, 6;");
+        let cond = Tree::Cond(
+            num(1),
+            num(2),
+            Box::new(Tree::Cond(num(3), num(4), num(5), Type::Int)),
+            Type::Int,
+        );
+        assert_eq!(trees, [*node(NodeKind::Comma, Box::new(cond), num(6))]);
+    }
+
+    #[test]
+    fn test_switch() {
+        let trees = parse_body("switch (1) { case 2: 3; default: case 4: break; }");
+        let body = Tree::Block(vec![
+            Tree::Case(0, num(3)),
+            Tree::Default(Box::new(Tree::Case(1, Box::new(Tree::Break)))),
+        ]);
+        let cases = Cases {
+            ty: Type::Int,
+            values: vec![2, 4],
+            has_default: true,
+        };
+        assert_eq!(trees, [Tree::Switch(num(1), Box::new(body), cases)]);
+    }
+
+    #[test]
+    fn test_goto() {
+        let trees = parse_body("goto end; end: 1;");
+        assert_eq!(
+            trees,
+            [
+                Tree::Goto("end".to_string()),
+                Tree::Label("end".to_string(), num(1))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_member() {
+        let trees = parse_body("struct s { char a; int b; } x; struct s *p; x.b; p->a;");
+        let member = |name: &str, ty: Type, offset: usize| Member {
+            name: name.to_string(),
+            ty,
+            offset,
+        };
+        match &trees[2] {
+            Tree::Member(tree, m) => {
+                assert!(matches!(**tree, Tree::Val(_)));
+                assert_eq!(*m, member("b", Type::Int, 4));
+            }
+            tree => panic!("unexpected tree: {:?}", tree),
+        }
+        match &trees[3] {
+            Tree::Member(tree, m) => {
+                assert!(matches!(**tree, Tree::Unary(NodeKind::Deref, _, _)));
+                assert_eq!(*m, member("a", Type::Char, 0));
+            }
+            tree => panic!("unexpected tree: {:?}", tree),
+        }
+    }
+
+    #[test]
+    fn test_enum() {
+        let trees = parse_body("enum E { A, B = 5, C }; enum E e; C; e;");
+        assert!(matches!(trees[2], Tree::Num(6, Type::Int)));
+        match &trees[3] {
+            Tree::Val(var) => assert_eq!(var.ty, Type::Int),
+            tree => panic!("unexpected tree: {:?}", tree),
+        }
+    }
+
+    #[test]
+    fn test_typedef() {
+        // Tがtypedef名ならT * x;は宣言、変数なら乗算になる
+        let trees = parse_body("typedef int T; T * x; x;");
+        match &trees[2] {
+            Tree::Val(var) => assert_eq!(var.ty, Type::pointer_to(Type::Int)),
+            tree => panic!("unexpected tree: {:?}", tree),
+        }
+        let trees = parse_body("int T; int x; T * x;");
+        assert!(matches!(trees[2], Tree::Node(NodeKind::Mul, _, _, _)));
+    }
+
+    #[test]
+    fn test_declarator() {
+        let trees =
+            parse_body("int (*a)[3]; char *(*tbl[4])(void); int (*fp)(int, long); a; tbl; fp;");
+        let types: Vec<Type> = trees[3..].iter().map(|tree| tree.ty().clone()).collect();
+        let func = Type::func(Type::pointer_to(Type::Char), vec![]);
+        assert_eq!(
+            types,
+            [
+                Type::pointer_to(Type::array_of(Type::Int, 3)),
+                Type::array_of(Type::pointer_to(func), 4),
+                Type::pointer_to(Type::func(Type::Int, vec![Type::Int, Type::Long])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_indirect_call() {
+        let trees = parse_body("int (*fp)(int); fp(1); (*fp)(2);");
+        for tree in &trees[1..] {
+            match tree {
+                Tree::IndirectCall(_, args, ty) => {
+                    assert_eq!(args.len(), 1);
+                    assert_eq!(*ty, Type::Int);
+                }
+                tree => panic!("unexpected tree: {:?}", tree),
+            }
+        }
     }
 }
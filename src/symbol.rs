@@ -0,0 +1,459 @@
+pub mod mysymbol {
+    use crate::types::mytype::{align_to, Type};
+
+    // 変数の記憶域
+    // アドレスの求め方が変わる
+    #[derive(Debug, PartialEq, Clone)]
+    pub enum Storage {
+        // 関数のフレームに置く
+        Local,
+        // .data, .bssにシンボルとして置く
+        Global,
+    }
+
+    // 変数一つ分の情報
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Var {
+        pub name: String,
+        pub ty: Type,
+        // rbpからのオフセット
+        // グローバル変数では使わない
+        pub offset: usize,
+        pub storage: Storage,
+    }
+
+    // ブロック一つ分のスコープ
+    struct Scope {
+        vars: Vec<Var>,
+        // 列挙子の名前と値
+        // 変数と同じ名前空間にあり、フレームの領域は使わない
+        enums: Vec<(String, i64)>,
+        // typedefで付けた型の名前
+        // これも変数と同じ名前空間にある
+        typedefs: Vec<(String, Type)>,
+        // 構造体、共用体、列挙型のタグ
+        // 変数とは別の名前空間になる
+        tags: Vec<(String, Type)>,
+        // スコープに入った時点のフレームの使用量
+        // スコープを抜けるとここまで巻き戻し、領域を再利用する
+        offset: usize,
+    }
+
+    pub struct SymbolTable {
+        scopes: Vec<Scope>,
+        // 定義済みの関数の名前と関数の型
+        functions: Vec<(String, Type)>,
+        // 文字列リテラルの中身
+        // 添字がラベルの番号になる
+        strings: Vec<Vec<u8>>,
+        offset: usize,
+        // フレームの使用量の最大値
+        stack_size: usize,
+    }
+
+    impl Default for SymbolTable {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl SymbolTable {
+        pub fn new() -> SymbolTable {
+            SymbolTable {
+                scopes: vec![Scope {
+                    vars: vec![],
+                    enums: vec![],
+                    typedefs: vec![],
+                    tags: vec![],
+                    offset: 0,
+                }],
+                functions: vec![],
+                strings: vec![],
+                offset: 0,
+                stack_size: 0,
+            }
+        }
+
+        // 関数ごとにフレームを使い直す
+        pub fn enter_function(&mut self) {
+            self.offset = 0;
+            self.stack_size = 0;
+            self.enter_scope();
+        }
+
+        // 関数のフレームに必要なバイト数を返す
+        pub fn leave_function(&mut self) -> usize {
+            self.leave_scope();
+            self.stack_size
+        }
+
+        pub fn enter_scope(&mut self) {
+            self.scopes.push(Scope {
+                vars: vec![],
+                enums: vec![],
+                typedefs: vec![],
+                tags: vec![],
+                offset: self.offset,
+            });
+        }
+
+        pub fn leave_scope(&mut self) {
+            if let Some(scope) = self.scopes.pop() {
+                self.offset = scope.offset;
+            }
+        }
+
+        // 現在のスコープに変数を宣言する
+        // 外側のスコープに同名の変数があっても隠す
+        // 同じスコープで名前が使われていればNoneを返す
+        pub fn declare(&mut self, name: &str, ty: Type) -> Option<Var> {
+            if self.scopes.last().is_some_and(|scope| scope.defines(name)) {
+                return None;
+            }
+            self.offset = align_to(self.offset + ty.size(), ty.align());
+            self.stack_size = self.stack_size.max(self.offset);
+            let var = Var {
+                name: name.to_string(),
+                ty,
+                offset: self.offset,
+                storage: Storage::Local,
+            };
+            if let Some(scope) = self.scopes.last_mut() {
+                scope.vars.push(var.clone());
+            }
+            Some(var)
+        }
+
+        // ファイルスコープに変数を宣言する
+        // 同名の変数が宣言済みであればNoneを返す
+        pub fn declare_global(&mut self, name: &str, ty: Type) -> Option<Var> {
+            let scope = &mut self.scopes[0];
+            if scope.defines(name) {
+                return None;
+            }
+            let var = Var {
+                name: name.to_string(),
+                ty,
+                offset: 0,
+                storage: Storage::Global,
+            };
+            scope.vars.push(var.clone());
+            Some(var)
+        }
+
+        pub fn declare_function(&mut self, name: &str, ty: Type) {
+            self.functions.push((name.to_string(), ty));
+        }
+
+        // 関数の型を探す
+        pub fn find_function(&self, name: &str) -> Option<&Type> {
+            self.functions
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, ty)| ty)
+        }
+
+        // 関数名を値として使うときの変数
+        // 関数の型を持つグローバル変数として扱い、アドレスが値になる
+        pub fn find_function_var(&self, name: &str) -> Option<Var> {
+            let ty = self.find_function(name)?.clone();
+            Some(Var {
+                name: name.to_string(),
+                ty,
+                offset: 0,
+                storage: Storage::Global,
+            })
+        }
+
+        // 文字列リテラルを登録して番号を返す
+        // 同じ中身の文字列は一つにまとめる
+        pub fn add_string(&mut self, bytes: Vec<u8>) -> usize {
+            match self.strings.iter().position(|s| *s == bytes) {
+                Some(id) => id,
+                None => {
+                    self.strings.push(bytes);
+                    self.strings.len() - 1
+                }
+            }
+        }
+
+        pub fn take_strings(&mut self) -> Vec<Vec<u8>> {
+            std::mem::take(&mut self.strings)
+        }
+
+        // 現在のスコープに列挙子を宣言する
+        // 同じスコープに同名の変数か列挙子があればfalseを返す
+        pub fn declare_enum(&mut self, name: &str, value: i64) -> bool {
+            match self.scopes.last_mut() {
+                Some(scope) if !scope.defines(name) => {
+                    scope.enums.push((name.to_string(), value));
+                    true
+                }
+                _ => false,
+            }
+        }
+
+        // 現在のスコープにtypedef名を宣言する
+        // 同じスコープで名前が使われていればfalseを返す
+        pub fn declare_typedef(&mut self, name: &str, ty: Type) -> bool {
+            match self.scopes.last_mut() {
+                Some(scope) if !scope.defines(name) => {
+                    scope.typedefs.push((name.to_string(), ty));
+                    true
+                }
+                _ => false,
+            }
+        }
+
+        // 現在のスコープにタグを宣言する
+        pub fn declare_tag(&mut self, name: &str, ty: Type) {
+            if let Some(scope) = self.scopes.last_mut() {
+                scope.tags.push((name.to_string(), ty));
+            }
+        }
+
+        // 内側のスコープから順にタグを探す
+        pub fn find_tag(&self, name: &str) -> Option<&Type> {
+            self.scopes
+                .iter()
+                .rev()
+                .find_map(|s| s.tags.iter().find(|(n, _)| n == name))
+                .map(|(_, ty)| ty)
+        }
+
+        // 現在のスコープだけからタグを探す
+        // 同じスコープでの再定義を見つけるのに使う
+        pub fn find_local_tag(&self, name: &str) -> Option<&Type> {
+            self.scopes
+                .last()?
+                .tags
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, ty)| ty)
+        }
+
+        // 内側のスコープから順に変数を探す
+        // 内側の列挙子やtypedef名に隠されていればNoneを返す
+        pub fn find(&self, name: &str) -> Option<&Var> {
+            match self.lookup(name)? {
+                Symbol::Var(var) => Some(var),
+                _ => None,
+            }
+        }
+
+        // 内側のスコープから順に列挙子を探して値を返す
+        pub fn find_enum(&self, name: &str) -> Option<i64> {
+            match self.lookup(name)? {
+                Symbol::Enum(value) => Some(value),
+                _ => None,
+            }
+        }
+
+        // 内側のスコープから順にtypedef名を探して型を返す
+        // 宣言か式かを見分けるのに使う
+        pub fn find_typedef(&self, name: &str) -> Option<&Type> {
+            match self.lookup(name)? {
+                Symbol::Typedef(ty) => Some(ty),
+                _ => None,
+            }
+        }
+
+        fn lookup(&self, name: &str) -> Option<Symbol<'_>> {
+            self.scopes.iter().rev().find_map(|s| {
+                if let Some(var) = s.vars.iter().rev().find(|v| v.name == name) {
+                    return Some(Symbol::Var(var));
+                }
+                if let Some((_, value)) = s.enums.iter().find(|(n, _)| n == name) {
+                    return Some(Symbol::Enum(*value));
+                }
+                s.typedefs
+                    .iter()
+                    .find(|(n, _)| n == name)
+                    .map(|(_, ty)| Symbol::Typedef(ty))
+            })
+        }
+    }
+
+    // 変数と同じ名前空間で見つかったもの
+    enum Symbol<'a> {
+        Var(&'a Var),
+        Enum(i64),
+        Typedef(&'a Type),
+    }
+
+    impl Scope {
+        // 変数、列挙子、typedef名として名前が使われているかどうか
+        fn defines(&self, name: &str) -> bool {
+            self.vars.iter().any(|v| v.name == name)
+                || self.enums.iter().any(|(n, _)| n == name)
+                || self.typedefs.iter().any(|(n, _)| n == name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        symbol::mysymbol::{Storage, SymbolTable},
+        types::mytype::Type,
+    };
+
+    #[test]
+    fn test_scope() {
+        let mut symbols = SymbolTable::new();
+        symbols.enter_function();
+        assert_eq!(symbols.declare("a", Type::Long).unwrap().offset, 8);
+
+        symbols.enter_scope();
+        assert_eq!(symbols.find("a").map(|v| v.offset), Some(8));
+        assert_eq!(symbols.declare("a", Type::Long).unwrap().offset, 16);
+        assert_eq!(symbols.declare("b", Type::Long).unwrap().offset, 24);
+        assert_eq!(symbols.declare("b", Type::Int), None);
+        assert_eq!(symbols.find("a").map(|v| v.offset), Some(16));
+        symbols.leave_scope();
+
+        assert_eq!(symbols.find("a").map(|v| v.offset), Some(8));
+        assert_eq!(symbols.find("b"), None);
+
+        // 抜けたブロックの領域は再利用される
+        symbols.enter_scope();
+        assert_eq!(symbols.declare("c", Type::Long).unwrap().offset, 16);
+        symbols.leave_scope();
+
+        assert_eq!(symbols.leave_function(), 24);
+        assert_eq!(symbols.find("a"), None);
+
+        // 関数ごとにフレームは作り直される
+        symbols.enter_function();
+        assert_eq!(symbols.declare("d", Type::Long).unwrap().offset, 8);
+        assert_eq!(symbols.leave_function(), 8);
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut symbols = SymbolTable::new();
+        symbols.enter_function();
+        // 型の大きさとアラインメントに合わせて配置される
+        assert_eq!(symbols.declare("c", Type::Char).unwrap().offset, 1);
+        assert_eq!(symbols.declare("i", Type::Int).unwrap().offset, 8);
+        assert_eq!(symbols.declare("s", Type::Short).unwrap().offset, 10);
+        assert_eq!(symbols.declare("l", Type::Long).unwrap().offset, 24);
+        assert_eq!(symbols.leave_function(), 24);
+    }
+
+    #[test]
+    fn test_array_layout() {
+        let mut symbols = SymbolTable::new();
+        symbols.enter_function();
+        // 配列は要素数分の領域を確保し、要素の型に合わせて配置される
+        assert_eq!(symbols.declare("c", Type::Char).unwrap().offset, 1);
+        let a = Type::array_of(Type::array_of(Type::Int, 3), 2);
+        assert_eq!(symbols.declare("a", a).unwrap().offset, 28);
+        assert_eq!(
+            symbols
+                .declare("s", Type::array_of(Type::Char, 5))
+                .unwrap()
+                .offset,
+            33
+        );
+        assert_eq!(symbols.leave_function(), 33);
+    }
+
+    #[test]
+    fn test_global() {
+        let mut symbols = SymbolTable::new();
+        assert!(symbols.declare_global("g", Type::Int).is_some());
+        assert!(symbols.declare_global("g", Type::Long).is_none());
+
+        // 関数の中からも見え、ローカル変数で隠せる
+        symbols.enter_function();
+        assert_eq!(
+            symbols.find("g").map(|v| &v.storage),
+            Some(&Storage::Global)
+        );
+        symbols.declare("g", Type::Int);
+        assert_eq!(symbols.find("g").map(|v| &v.storage), Some(&Storage::Local));
+        assert_eq!(symbols.leave_function(), 4);
+        assert_eq!(
+            symbols.find("g").map(|v| &v.storage),
+            Some(&Storage::Global)
+        );
+    }
+
+    #[test]
+    fn test_string() {
+        let mut symbols = SymbolTable::new();
+        assert_eq!(symbols.add_string(b"abc\0".to_vec()), 0);
+        assert_eq!(symbols.add_string(b"xy\0".to_vec()), 1);
+        assert_eq!(symbols.add_string(b"abc\0".to_vec()), 0);
+        assert_eq!(
+            symbols.take_strings(),
+            [b"abc\0".to_vec(), b"xy\0".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_tag() {
+        let mut symbols = SymbolTable::new();
+        symbols.declare_tag("s", Type::Int);
+        symbols.declare("s", Type::Long);
+        assert_eq!(symbols.find_tag("s"), Some(&Type::Int));
+        assert_eq!(symbols.find("s").map(|v| v.ty.clone()), Some(Type::Long));
+
+        symbols.enter_scope();
+        assert_eq!(symbols.find_tag("s"), Some(&Type::Int));
+        assert_eq!(symbols.find_local_tag("s"), None);
+        symbols.declare_tag("s", Type::Char);
+        assert_eq!(symbols.find_local_tag("s"), Some(&Type::Char));
+        symbols.leave_scope();
+
+        assert_eq!(symbols.find_tag("s"), Some(&Type::Int));
+    }
+
+    #[test]
+    fn test_enum() {
+        let mut symbols = SymbolTable::new();
+        assert!(symbols.declare_enum("A", 3));
+        assert!(!symbols.declare_enum("A", 4));
+        assert_eq!(symbols.declare_global("A", Type::Int), None);
+        assert_eq!(symbols.find_enum("A"), Some(3));
+        assert_eq!(symbols.find("A"), None);
+
+        // 内側のスコープの変数は外側の列挙子を隠す
+        symbols.enter_function();
+        let var = symbols.declare("A", Type::Int).unwrap();
+        assert_eq!(symbols.find_enum("A"), None);
+        assert_eq!(symbols.find("A"), Some(&var));
+        symbols.enter_scope();
+        assert!(symbols.declare_enum("A", 5));
+        assert_eq!(symbols.find_enum("A"), Some(5));
+        assert_eq!(symbols.find("A"), None);
+        symbols.leave_scope();
+        // 列挙子はフレームの領域を使わない
+        assert_eq!(symbols.leave_function(), 4);
+        assert_eq!(symbols.find_enum("A"), Some(3));
+    }
+
+    #[test]
+    fn test_typedef() {
+        let mut symbols = SymbolTable::new();
+        assert!(symbols.declare_typedef("T", Type::Long));
+        assert!(!symbols.declare_typedef("T", Type::Int));
+        assert!(!symbols.declare_enum("T", 0));
+        assert_eq!(symbols.declare_global("T", Type::Int), None);
+        assert_eq!(symbols.find_typedef("T"), Some(&Type::Long));
+        assert_eq!(symbols.find("T"), None);
+
+        // 内側のスコープの変数はtypedef名を隠す
+        symbols.enter_function();
+        symbols.declare("T", Type::Int);
+        assert_eq!(symbols.find_typedef("T"), None);
+        symbols.enter_scope();
+        assert!(symbols.declare_typedef("T", Type::Char));
+        assert_eq!(symbols.find_typedef("T"), Some(&Type::Char));
+        symbols.leave_scope();
+        // typedef名はフレームの領域を使わない
+        assert_eq!(symbols.leave_function(), 4);
+        assert_eq!(symbols.find_typedef("T"), Some(&Type::Long));
+    }
+}
@@ -1,24 +1,46 @@
 pub mod mytoken {
     use core::fmt;
 
-    use crate::numtype::mynumtype::NumType;
+    use crate::{numtype::mynumtype::NumType, types::mytype::Type};
 
     #[derive(Debug, PartialEq)]
     pub enum Token {
         Operator(OperatorKind),
+        Keyword(KeywordKind),
         EOF,
     }
 
     #[derive(Debug, PartialEq)]
     pub enum ErrorToken {
         InvaildChar(char),
+        // 閉じられていない文字列・文字リテラルの開始位置(行, 列)
+        UnterminatedLiteral(usize, usize),
+        // 空の、または二文字以上の文字リテラルの開始位置(行, 列)
+        EmptyCharLiteral(usize, usize),
+        MultiCharLiteral(usize, usize),
+        // 不正なエスケープの文字と\の位置(行, 列)
+        InvalidEscape(char, usize, usize),
+        // 不正な、または大きすぎる整数リテラルの開始位置(行, 列)
+        InvalidNumber(usize, usize),
+        NumberOverflow(usize, usize),
+        // 閉じられていないコメントの開始位置(行, 列)
+        UnterminatedComment(usize, usize),
     }
 
     #[derive(Debug, PartialEq)]
     pub enum OperatorKind {
         Ident(Ident),
         Semi,
-        Operand(NumType),
+        Comma,
+        Question,
+        Colon,
+        Dot,
+        Arrow,
+        // 接尾辞と値の大きさから決まる型を持つ
+        Operand(NumType, Type),
+        // エスケープを解釈した後のバイト列
+        StringLiteral(Vec<u8>),
+        CharLiteral(u8),
         Equal,
         Equality,
         Nonequality,
@@ -30,8 +52,63 @@ pub mod mytoken {
         Sub,
         Mul,
         Div,
+        Percent,
+        Ampersand,
+        Pipe,
+        Caret,
+        Tilde,
+        Shl,
+        Shr,
+        AddAssign,
+        SubAssign,
+        MulAssign,
+        DivAssign,
+        ModAssign,
+        AndAssign,
+        OrAssign,
+        XorAssign,
+        ShlAssign,
+        ShrAssign,
+        Inc,
+        Dec,
+        LogicalAnd,
+        LogicalOr,
+        Not,
         LParen,
         RParen,
+        LBrace,
+        RBrace,
+        LBracket,
+        RBracket,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum KeywordKind {
+        Return,
+        If,
+        Else,
+        While,
+        Do,
+        For,
+        Break,
+        Continue,
+        Switch,
+        Case,
+        Default,
+        Goto,
+        Struct,
+        Union,
+        Enum,
+        Typedef,
+        Void,
+        Int,
+        Long,
+        Char,
+        Short,
+        Signed,
+        Unsigned,
+        Sizeof,
+        Alignof,
     }
 
     #[derive(Debug, PartialEq)]
@@ -47,12 +124,53 @@ pub mod mytoken {
         }
     }
 
+    impl KeywordKind {
+        // 予約語であればKeywordKindを返す
+        pub fn from_name(name: &str) -> Option<KeywordKind> {
+            match name {
+                "return" => Some(KeywordKind::Return),
+                "if" => Some(KeywordKind::If),
+                "else" => Some(KeywordKind::Else),
+                "while" => Some(KeywordKind::While),
+                "do" => Some(KeywordKind::Do),
+                "for" => Some(KeywordKind::For),
+                "break" => Some(KeywordKind::Break),
+                "continue" => Some(KeywordKind::Continue),
+                "switch" => Some(KeywordKind::Switch),
+                "case" => Some(KeywordKind::Case),
+                "default" => Some(KeywordKind::Default),
+                "goto" => Some(KeywordKind::Goto),
+                "struct" => Some(KeywordKind::Struct),
+                "union" => Some(KeywordKind::Union),
+                "enum" => Some(KeywordKind::Enum),
+                "typedef" => Some(KeywordKind::Typedef),
+                "void" => Some(KeywordKind::Void),
+                "int" => Some(KeywordKind::Int),
+                "long" => Some(KeywordKind::Long),
+                "char" => Some(KeywordKind::Char),
+                "short" => Some(KeywordKind::Short),
+                "signed" => Some(KeywordKind::Signed),
+                "unsigned" => Some(KeywordKind::Unsigned),
+                "sizeof" => Some(KeywordKind::Sizeof),
+                "_Alignof" => Some(KeywordKind::Alignof),
+                _ => None,
+            }
+        }
+    }
+
     impl fmt::Display for OperatorKind {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
                 OperatorKind::Ident(c) => write!(f, "{}", c.name),
                 OperatorKind::Semi => write!(f, ";"),
-                OperatorKind::Operand(n) => write!(f, "{}", n),
+                OperatorKind::Comma => write!(f, ","),
+                OperatorKind::Question => write!(f, "?"),
+                OperatorKind::Colon => write!(f, ":"),
+                OperatorKind::Dot => write!(f, "."),
+                OperatorKind::Arrow => write!(f, "->"),
+                OperatorKind::Operand(n, _) => write!(f, "{}", n),
+                OperatorKind::StringLiteral(s) => write!(f, "\"{}\"", String::from_utf8_lossy(s)),
+                OperatorKind::CharLiteral(c) => write!(f, "'{}'", *c as char),
                 OperatorKind::Equal => write!(f, "="),
                 OperatorKind::Equality => write!(f, "=="),
                 OperatorKind::Nonequality => write!(f, "!="),
@@ -64,8 +182,66 @@ pub mod mytoken {
                 OperatorKind::Sub => write!(f, "-"),
                 OperatorKind::Mul => write!(f, "*"),
                 OperatorKind::Div => write!(f, "/"),
+                OperatorKind::Percent => write!(f, "%"),
+                OperatorKind::Ampersand => write!(f, "&"),
+                OperatorKind::Pipe => write!(f, "|"),
+                OperatorKind::Caret => write!(f, "^"),
+                OperatorKind::Tilde => write!(f, "~"),
+                OperatorKind::Shl => write!(f, "<<"),
+                OperatorKind::Shr => write!(f, ">>"),
+                OperatorKind::AddAssign => write!(f, "+="),
+                OperatorKind::SubAssign => write!(f, "-="),
+                OperatorKind::MulAssign => write!(f, "*="),
+                OperatorKind::DivAssign => write!(f, "/="),
+                OperatorKind::ModAssign => write!(f, "%="),
+                OperatorKind::AndAssign => write!(f, "&="),
+                OperatorKind::OrAssign => write!(f, "|="),
+                OperatorKind::XorAssign => write!(f, "^="),
+                OperatorKind::ShlAssign => write!(f, "<<="),
+                OperatorKind::ShrAssign => write!(f, ">>="),
+                OperatorKind::Inc => write!(f, "++"),
+                OperatorKind::Dec => write!(f, "--"),
+                OperatorKind::LogicalAnd => write!(f, "&&"),
+                OperatorKind::LogicalOr => write!(f, "||"),
+                OperatorKind::Not => write!(f, "!"),
                 OperatorKind::LParen => write!(f, "("),
                 OperatorKind::RParen => write!(f, ")"),
+                OperatorKind::LBrace => write!(f, "{{"),
+                OperatorKind::RBrace => write!(f, "}}"),
+                OperatorKind::LBracket => write!(f, "["),
+                OperatorKind::RBracket => write!(f, "]"),
+            }
+        }
+    }
+
+    impl fmt::Display for KeywordKind {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                KeywordKind::Return => write!(f, "return"),
+                KeywordKind::If => write!(f, "if"),
+                KeywordKind::Else => write!(f, "else"),
+                KeywordKind::While => write!(f, "while"),
+                KeywordKind::Do => write!(f, "do"),
+                KeywordKind::For => write!(f, "for"),
+                KeywordKind::Break => write!(f, "break"),
+                KeywordKind::Continue => write!(f, "continue"),
+                KeywordKind::Switch => write!(f, "switch"),
+                KeywordKind::Case => write!(f, "case"),
+                KeywordKind::Default => write!(f, "default"),
+                KeywordKind::Goto => write!(f, "goto"),
+                KeywordKind::Struct => write!(f, "struct"),
+                KeywordKind::Union => write!(f, "union"),
+                KeywordKind::Enum => write!(f, "enum"),
+                KeywordKind::Typedef => write!(f, "typedef"),
+                KeywordKind::Void => write!(f, "void"),
+                KeywordKind::Int => write!(f, "int"),
+                KeywordKind::Long => write!(f, "long"),
+                KeywordKind::Char => write!(f, "char"),
+                KeywordKind::Short => write!(f, "short"),
+                KeywordKind::Signed => write!(f, "signed"),
+                KeywordKind::Unsigned => write!(f, "unsigned"),
+                KeywordKind::Sizeof => write!(f, "sizeof"),
+                KeywordKind::Alignof => write!(f, "_Alignof"),
             }
         }
     }
@@ -1,5 +1,9 @@
 pub mod mytree {
-    use crate::{lexer::mylexer::Lexer, numtype::mynumtype::NumType, token::mytoken::Ident};
+    use crate::{
+        numtype::mynumtype::NumType,
+        symbol::mysymbol::Var,
+        types::mytype::{Member, Type},
+    };
 
     #[derive(Debug, PartialEq)]
     pub enum NodeKind {
@@ -13,26 +17,200 @@ pub mod mytree {
         Sub,         // -
         Mul,         // *
         Div,         // /
+        Mod,         // %
+        BitAnd,      // &
+        BitOr,       // |
+        BitXor,      // ^
+        BitNot,      // ~
+        Shl,         // <<
+        Shr,         // >>
+        Addr,        // &
+        Deref,       // *
+        LogicalAnd,  // &&
+        LogicalOr,   // ||
+        Not,         // !
+        Comma,       // ,
     }
 
+    // 式の木は末尾に式の型を持つ
     #[derive(Debug, PartialEq)]
     pub enum Tree {
-        Num(NumType),
-        Val(usize),
-        Node(NodeKind, Box<Tree>, Box<Tree>),
+        Num(NumType, Type),
+        Val(Var),
+        // 文字列リテラルの番号
+        Str(usize, Type),
+        Node(NodeKind, Box<Tree>, Box<Tree>, Type),
+        Unary(NodeKind, Box<Tree>, Type),
+        // 構造体のメンバー s.m
+        Member(Box<Tree>, Member),
+        // 複合代入 a op= b
+        // 左辺のアドレスは一度だけ計算する
+        AssignOp(NodeKind, Box<Tree>, Box<Tree>, Type),
+        // 条件演算子 cond ? then : els
+        Cond(Box<Tree>, Box<Tree>, Box<Tree>, Type),
+        Return(Box<Tree>),
+        If(Box<Tree>, Box<Tree>, Option<Box<Tree>>),
+        While(Box<Tree>, Box<Tree>),
+        DoWhile(Box<Tree>, Box<Tree>),
+        For(
+            Option<Box<Tree>>,
+            Option<Box<Tree>>,
+            Option<Box<Tree>>,
+            Box<Tree>,
+        ),
+        Break,
+        Continue,
+        Switch(Box<Tree>, Box<Tree>, Cases),
+        // caseの番号とそれに続く文
+        Case(usize, Box<Tree>),
+        Default(Box<Tree>),
+        // ラベルの名前はソースに書かれたもの
+        Goto(String),
+        Label(String, Box<Tree>),
+        Block(Vec<Tree>),
+        Call(String, Vec<Tree>, Type),
+        // 関数ポインタを通した呼び出し
+        // 先頭の式の値が呼び出す先のアドレスになる
+        IndirectCall(Box<Tree>, Vec<Tree>, Type),
+        // 型変換 (type)expr
+        Cast(Box<Tree>, Type),
+    }
+
+    // switch文に現れたcaseとdefault
+    // caseの番号はvaluesの添字になる
+    #[derive(Debug, PartialEq)]
+    pub struct Cases {
+        // 比較する値の型
+        pub ty: Type,
+        pub values: Vec<i64>,
+        pub has_default: bool,
+    }
+
+    impl Cases {
+        pub fn new(ty: Type) -> Cases {
+            Cases {
+                ty,
+                values: vec![],
+                has_default: false,
+            }
+        }
+    }
+
+    // 関数定義
+    #[derive(Debug, PartialEq)]
+    pub struct Function {
+        pub name: String,
+        pub ty: Type,
+        // 引数を退避する先の変数
+        pub params: Vec<Var>,
+        pub body: Tree,
+        pub stack_size: usize,
+    }
+
+    // グローバル変数の定義
+    #[derive(Debug, PartialEq)]
+    pub struct GlobalVar {
+        pub var: Var,
+        // 初期値がなければ.bssに置く
+        pub init: Option<i64>,
+    }
+
+    // プログラム全体
+    #[derive(Debug, PartialEq)]
+    pub struct Program {
+        pub functions: Vec<Function>,
+        pub globals: Vec<GlobalVar>,
+        // 文字列リテラルの中身(終端の\0を含む)
+        pub strings: Vec<Vec<u8>>,
     }
 
     impl Tree {
         pub fn new_tree(kind: NodeKind, lhs: Tree, rhs: Tree) -> Tree {
-            Tree::Node(kind, Box::new(lhs), Box::new(rhs))
+            let ty = match kind {
+                NodeKind::Assign => lhs.ty().clone(),
+                NodeKind::Equality
+                | NodeKind::Nonequality
+                | NodeKind::Less
+                | NodeKind::LessOrEqual
+                | NodeKind::LogicalAnd
+                | NodeKind::LogicalOr => Type::Int,
+                // シフトの結果は左辺の型になる
+                NodeKind::Shl | NodeKind::Shr => lhs.ty().promote(),
+                // コンマ式の値は右辺になり、配列と関数はポインタとして扱う
+                NodeKind::Comma => rhs.ty().decay(),
+                _ => Type::common_type(lhs.ty(), rhs.ty()),
+            };
+            Tree::Node(kind, Box::new(lhs), Box::new(rhs), ty)
         }
 
+        pub fn new_unary(kind: NodeKind, tree: Tree, ty: Type) -> Tree {
+            Tree::Unary(kind, Box::new(tree), ty)
+        }
+
+        // intに収まらない数はlongになる
         pub fn new_num(num: NumType) -> Tree {
-            Tree::Num(num)
+            let ty = if num <= i32::MAX as NumType {
+                Type::Int
+            } else {
+                Type::Long
+            };
+            Tree::Num(num, ty)
+        }
+
+        pub fn new_return(tree: Tree) -> Tree {
+            Tree::Return(Box::new(tree))
+        }
+
+        pub fn new_if(cond: Tree, then: Tree, els: Option<Tree>) -> Tree {
+            Tree::If(Box::new(cond), Box::new(then), els.map(Box::new))
+        }
+
+        pub fn new_while(cond: Tree, body: Tree) -> Tree {
+            Tree::While(Box::new(cond), Box::new(body))
+        }
+
+        pub fn new_do_while(body: Tree, cond: Tree) -> Tree {
+            Tree::DoWhile(Box::new(body), Box::new(cond))
+        }
+
+        pub fn new_for(
+            init: Option<Tree>,
+            cond: Option<Tree>,
+            step: Option<Tree>,
+            body: Tree,
+        ) -> Tree {
+            Tree::For(
+                init.map(Box::new),
+                cond.map(Box::new),
+                step.map(Box::new),
+                Box::new(body),
+            )
+        }
+
+        pub fn new_val(var: Var) -> Tree {
+            Tree::Val(var)
+        }
+
+        pub fn new_call(name: String, args: Vec<Tree>, ty: Type) -> Tree {
+            Tree::Call(name, args, ty)
         }
 
-        pub fn new_val(i: Ident, lexer: &mut Lexer) -> Tree {
-            Tree::Val(lexer.calc_offset(i))
+        // 式の型
+        pub fn ty(&self) -> &Type {
+            match self {
+                Tree::Num(_, ty)
+                | Tree::Node(_, _, _, ty)
+                | Tree::Str(_, ty)
+                | Tree::Unary(_, _, ty)
+                | Tree::AssignOp(_, _, _, ty)
+                | Tree::Cond(_, _, _, ty)
+                | Tree::Call(_, _, ty)
+                | Tree::IndirectCall(_, _, ty)
+                | Tree::Cast(_, ty) => ty,
+                Tree::Val(var) => &var.ty,
+                Tree::Member(_, member) => &member.ty,
+                _ => unreachable!("statement has no type"),
+            }
         }
     }
 }
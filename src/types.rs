@@ -0,0 +1,364 @@
+pub mod mytype {
+    use std::{cell::RefCell, fmt, rc::Rc};
+
+    // nをalignの倍数に切り上げる
+    pub fn align_to(n: usize, align: usize) -> usize {
+        n.div_ceil(align) * align
+    }
+
+    #[derive(Debug, PartialEq, Clone)]
+    pub enum Type {
+        Char,
+        Short,
+        Int,
+        Long,
+        UChar,
+        UShort,
+        UInt,
+        ULong,
+        Ptr(Box<Type>),
+        // 要素の型と要素数
+        Array(Box<Type>, usize),
+        // 構造体と共用体
+        Struct(Rc<Aggregate>),
+        // 戻り値の型と引数の型
+        Func(Box<Type>, Vec<Type>),
+    }
+
+    // 構造体、共用体のメンバー
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Member {
+        pub name: String,
+        pub ty: Type,
+        // 先頭からのバイト数
+        // 共用体のメンバーはすべて0になる
+        pub offset: usize,
+    }
+
+    // 構造体、共用体の中身
+    // 同じタグの型は一つの実体を共有する
+    // 自身へのポインタをメンバーに持てるよう、メンバーは後から決める
+    pub struct Aggregate {
+        pub tag: Option<String>,
+        pub is_union: bool,
+        layout: RefCell<Option<Layout>>,
+    }
+
+    struct Layout {
+        members: Vec<Member>,
+        size: usize,
+        align: usize,
+    }
+
+    impl Aggregate {
+        // メンバーの決まっていない不完全な型を作る
+        pub fn new(tag: Option<String>, is_union: bool) -> Rc<Aggregate> {
+            Rc::new(Aggregate {
+                tag,
+                is_union,
+                layout: RefCell::new(None),
+            })
+        }
+
+        // メンバーの配置を決める
+        // 構造体は宣言順に各メンバーをその型の境界に揃えて並べる
+        // 共用体はすべてのメンバーを先頭に重ね、最も大きいメンバーの大きさになる
+        // どちらも全体の大きさは最も厳しい境界に揃える
+        pub fn define(&self, members: Vec<(String, Type)>) {
+            let mut offset = 0;
+            let mut size = 0;
+            let mut align = 1;
+            let members = members
+                .into_iter()
+                .map(|(name, ty)| {
+                    if !self.is_union {
+                        offset = align_to(offset, ty.align());
+                    }
+                    align = align.max(ty.align());
+                    size = size.max(offset + ty.size());
+                    let member = Member { name, ty, offset };
+                    if !self.is_union {
+                        offset += member.ty.size();
+                    }
+                    member
+                })
+                .collect();
+            *self.layout.borrow_mut() = Some(Layout {
+                members,
+                size: align_to(size, align),
+                align,
+            });
+        }
+
+        pub fn is_complete(&self) -> bool {
+            self.layout.borrow().is_some()
+        }
+
+        pub fn member(&self, name: &str) -> Option<Member> {
+            self.layout
+                .borrow()
+                .as_ref()?
+                .members
+                .iter()
+                .find(|m| m.name == name)
+                .cloned()
+        }
+
+        // 不完全な型の大きさは0として扱う
+        fn size(&self) -> usize {
+            self.layout.borrow().as_ref().map_or(0, |l| l.size)
+        }
+
+        fn align(&self) -> usize {
+            self.layout.borrow().as_ref().map_or(1, |l| l.align)
+        }
+    }
+
+    // 同じ宣言から作られた型だけを同じ型とみなす
+    impl PartialEq for Aggregate {
+        fn eq(&self, other: &Self) -> bool {
+            std::ptr::eq(self, other)
+        }
+    }
+
+    // メンバーは自身を指すことがあるので、タグだけを表示する
+    impl fmt::Debug for Aggregate {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let kind = if self.is_union { "union" } else { "struct" };
+            match &self.tag {
+                Some(tag) => write!(f, "{} {}", kind, tag),
+                None => write!(f, "{} <anonymous>", kind),
+            }
+        }
+    }
+
+    impl Type {
+        // バイト数
+        pub fn size(&self) -> usize {
+            match self {
+                Type::Char | Type::UChar => 1,
+                Type::Short | Type::UShort => 2,
+                Type::Int | Type::UInt => 4,
+                Type::Long | Type::ULong | Type::Ptr(_) => 8,
+                Type::Array(base, len) => base.size() * len,
+                Type::Struct(aggregate) => aggregate.size(),
+                // 関数は大きさを持たない
+                Type::Func(_, _) => 0,
+            }
+        }
+
+        pub fn align(&self) -> usize {
+            match self {
+                Type::Array(base, _) => base.align(),
+                Type::Struct(aggregate) => aggregate.align(),
+                _ => self.size(),
+            }
+        }
+
+        pub fn pointer_to(ty: Type) -> Type {
+            Type::Ptr(Box::new(ty))
+        }
+
+        pub fn array_of(ty: Type, len: usize) -> Type {
+            Type::Array(Box::new(ty), len)
+        }
+
+        pub fn func(ret: Type, params: Vec<Type>) -> Type {
+            Type::Func(Box::new(ret), params)
+        }
+
+        // ポインタであれば指す先の型を、配列であれば要素の型を返す
+        pub fn base(&self) -> Option<&Type> {
+            match self {
+                Type::Ptr(base) | Type::Array(base, _) => Some(base),
+                _ => None,
+            }
+        }
+
+        pub fn is_array(&self) -> bool {
+            matches!(self, Type::Array(_, _))
+        }
+
+        pub fn is_struct(&self) -> bool {
+            matches!(self, Type::Struct(_))
+        }
+
+        pub fn is_func(&self) -> bool {
+            matches!(self, Type::Func(_, _))
+        }
+
+        pub fn is_integer(&self) -> bool {
+            !self.is_struct() && !self.is_func() && self.base().is_none()
+        }
+
+        // 式の値として使うときの型
+        // 配列は先頭の要素へのポインタに、関数は関数へのポインタになる
+        pub fn decay(&self) -> Type {
+            match self {
+                Type::Array(base, _) => Type::Ptr(base.clone()),
+                Type::Func(_, _) => Type::pointer_to(self.clone()),
+                ty => ty.clone(),
+            }
+        }
+
+        // 関数か関数へのポインタであれば戻り値の型を返す
+        pub fn return_type(&self) -> Option<&Type> {
+            match self {
+                Type::Func(ret, _) => Some(ret),
+                Type::Ptr(base) => match base.as_ref() {
+                    Type::Func(ret, _) => Some(ret),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+
+        // 大きさが決まっているかどうか
+        // 不完全な型の変数は作れない
+        pub fn is_complete(&self) -> bool {
+            match self {
+                Type::Array(base, _) => base.is_complete(),
+                Type::Struct(aggregate) => aggregate.is_complete(),
+                Type::Func(_, _) => false,
+                _ => true,
+            }
+        }
+
+        pub fn is_unsigned(&self) -> bool {
+            matches!(self, Type::UChar | Type::UShort | Type::UInt | Type::ULong)
+        }
+
+        // 同じ大きさの符号なしの型
+        fn to_unsigned(&self) -> Type {
+            match self {
+                Type::Char => Type::UChar,
+                Type::Short => Type::UShort,
+                Type::Int => Type::UInt,
+                Type::Long => Type::ULong,
+                ty => ty.clone(),
+            }
+        }
+
+        // 整数拡張
+        // intより小さい型はintになる
+        pub fn promote(&self) -> Type {
+            if self.is_integer() && self.size() < 4 {
+                Type::Int
+            } else {
+                self.clone()
+            }
+        }
+
+        // 二項演算の結果の型
+        // 整数拡張した上で大きい方の型に揃え、同じ大きさなら符号なしを優先する
+        // ポインタはlongとして扱う
+        pub fn common_type(lhs: &Type, rhs: &Type) -> Type {
+            if lhs.base().is_some() || rhs.base().is_some() {
+                return Type::Long;
+            }
+            let (lhs, rhs) = (lhs.promote(), rhs.promote());
+            if lhs.size() != rhs.size() {
+                return if lhs.size() > rhs.size() { lhs } else { rhs };
+            }
+            if lhs.is_unsigned() || rhs.is_unsigned() {
+                lhs.to_unsigned()
+            } else {
+                lhs
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::mytype::{Aggregate, Type};
+
+    #[test]
+    fn test_common_type() {
+        assert_eq!(Type::common_type(&Type::Char, &Type::Short), Type::Int);
+        assert_eq!(Type::common_type(&Type::UChar, &Type::Int), Type::Int);
+        assert_eq!(Type::common_type(&Type::Int, &Type::UInt), Type::UInt);
+        assert_eq!(Type::common_type(&Type::UInt, &Type::Long), Type::Long);
+        assert_eq!(Type::common_type(&Type::ULong, &Type::Int), Type::ULong);
+        assert_eq!(Type::common_type(&Type::Long, &Type::ULong), Type::ULong);
+        let ptr = Type::pointer_to(Type::Char);
+        assert_eq!(Type::common_type(&ptr, &Type::Int), Type::Long);
+    }
+
+    #[test]
+    fn test_struct_layout() {
+        // struct { char a; int b; char c; long d; short e; }
+        let aggregate = Aggregate::new(None, false);
+        aggregate.define(vec![
+            ("a".to_string(), Type::Char),
+            ("b".to_string(), Type::Int),
+            ("c".to_string(), Type::Char),
+            ("d".to_string(), Type::Long),
+            ("e".to_string(), Type::Short),
+        ]);
+        let offsets: Vec<usize> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|name| aggregate.member(name).unwrap().offset)
+            .collect();
+        assert_eq!(offsets, [0, 4, 8, 16, 24]);
+        let ty = Type::Struct(aggregate);
+        assert_eq!((ty.size(), ty.align()), (32, 8));
+
+        // 配列のメンバーは要素の境界に揃える
+        let inner = Aggregate::new(Some("inner".to_string()), false);
+        inner.define(vec![
+            ("c".to_string(), Type::Char),
+            ("s".to_string(), Type::array_of(Type::Short, 3)),
+        ]);
+        assert_eq!(inner.member("s").map(|m| m.offset), Some(2));
+        let inner = Type::Struct(inner);
+        assert_eq!((inner.size(), inner.align()), (8, 2));
+
+        // 不完全な型は定義するまで大きさが決まらない
+        let incomplete = Aggregate::new(Some("node".to_string()), false);
+        let ty = Type::Struct(incomplete.clone());
+        assert!(!ty.is_complete());
+        incomplete.define(vec![("next".to_string(), Type::pointer_to(ty.clone()))]);
+        assert!(ty.is_complete());
+        assert_eq!(ty.size(), 8);
+        assert_ne!(ty, inner);
+    }
+
+    #[test]
+    fn test_union_layout() {
+        // union { char a; int b[3]; long c; }
+        let aggregate = Aggregate::new(None, true);
+        aggregate.define(vec![
+            ("a".to_string(), Type::Char),
+            ("b".to_string(), Type::array_of(Type::Int, 3)),
+            ("c".to_string(), Type::Long),
+        ]);
+        for name in ["a", "b", "c"] {
+            assert_eq!(aggregate.member(name).map(|m| m.offset), Some(0));
+        }
+        let ty = Type::Struct(aggregate);
+        assert_eq!((ty.size(), ty.align()), (16, 8));
+
+        let aggregate = Aggregate::new(None, true);
+        aggregate.define(vec![
+            ("a".to_string(), Type::array_of(Type::Char, 5)),
+            ("b".to_string(), Type::Short),
+        ]);
+        let ty = Type::Struct(aggregate);
+        assert_eq!((ty.size(), ty.align()), (6, 2));
+    }
+
+    #[test]
+    fn test_func() {
+        // int (*)(int, long)
+        let func = Type::func(Type::Int, vec![Type::Int, Type::Long]);
+        let ptr = Type::pointer_to(func.clone());
+        assert!(!func.is_integer() && !func.is_complete());
+        assert_eq!(ptr.size(), 8);
+        assert_eq!(func.decay(), ptr);
+        assert_eq!(func.return_type(), Some(&Type::Int));
+        assert_eq!(ptr.return_type(), Some(&Type::Int));
+        assert_eq!(Type::pointer_to(ptr.clone()).return_type(), None);
+        assert_ne!(func, Type::func(Type::Int, vec![Type::Int]));
+    }
+}
@@ -7,13 +7,34 @@ MAIN=./source/main
 
 mkdir -p ./source
 
+# 関数呼び出しのテストに使う補助関数
+cat <<EOF | gcc -xc -c -O0 -fno-omit-frame-pointer -o ./source/tmp2.o -
+#include <stdio.h>
+int ret3() { return 3; }
+int ret5() { return 5; }
+long plus(long x, long y) { return x + y; }
+long minus(long x, long y) { return x - y; }
+long add6(long a, long b, long c, long d, long e, long f) {
+    return a + b + c + d + e + f;
+}
+long sub8(long a, long b, long c, long d, long e, long f, long g, long h) {
+    return a - b - c - d - e - f - g - h;
+}
+long at9(long a, long b, long c, long d, long e, long f, long g, long h, long i) {
+    return i;
+}
+/* 呼び出し時にスタックが16バイト境界に揃っていれば1 */
+long aligned() { return ((unsigned long)__builtin_frame_address(0) % 16) == 0; }
+long print(long x) { return printf("%ld\\n", x); }
+EOF
+
 assert() {
     expected="$1"
     input="$2"
 
-    echo $input > $MAINC
-    $IOC $MAINC $MAINS
-    gcc $MAINS -o $MAIN
+    echo "$input" > $MAINC
+    $IOC $MAINC $MAINS || exit 1
+    gcc $MAINS ./source/tmp2.o -o $MAIN || exit 1
     $MAIN
     actual="$?"
 
@@ -25,20 +46,619 @@ assert() {
     fi
 }
 
-# ダブルクオーテーションの中でも*の後ろに空白文字があるとメタ文字と解釈されてファイル一覧に展開されるから注意
-
-assert 0 "0;"
-assert 42 "42;"
-assert 41 " 12 + 34 -5 ;"
-assert 47 "5 + 6 *7;"
-assert 15 "5*(9-6);"
-assert 4 "(3+5)/2;"
-assert 10 "-10+20;"
-assert 1 "1==1;"
-assert 1 "2 *3!=3-1;"
-assert 0 "32/(1==1)<2;"
-assert 2 "a=1+1;a;"
-assert 14 "a = 3; b = 5*6 - 8; a + b / 2;"
-assert 15 "row = 3; column=5; row*column;"
+# gccでコンパイルした結果と同じ値を返すことを確かめる
+assert_gcc() {
+    input="$1"
+
+    echo "$input" | gcc -xc -w -o ./source/gcc - || exit 1
+    ./source/gcc
+    assert "$?" "$input"
+}
+
+assert 0 "int main() { 0; }"
+assert 42 "int main() { 42; }"
+assert 41 "int main() {  12 + 34 -5 ; }"
+assert 47 "int main() { 5 + 6 *7; }"
+assert 15 "int main() { 5*(9-6); }"
+assert 4 "int main() { (3+5)/2; }"
+assert 10 "int main() { -10+20; }"
+assert 1 "int main() { 1==1; }"
+assert 1 "int main() { 2 *3!=3-1; }"
+assert 0 "int main() { 32/(1==1)<2; }"
+assert 2 "int main() { int a=1+1;a; }"
+assert 14 "int main() { int a = 3; int b = 5*6 - 8; a + b / 2; }"
+assert 15 "int main() { int row = 3; int column=5; row*column; }"
+assert 14 "int main() { int a = 3; int b = 5*6 - 8; return a + b / 2; }"
+assert 5 "int main() { return 5; return 8; }"
+assert 3 "int main() { int a = 3; return a; a = 7; a; }"
+assert 6 "int main() { int returns = 6; return returns; }"
+assert 3 "int main() { if (0) return 2; return 3; }"
+assert 3 "int main() { if (1-1) return 2; return 3; }"
+assert 2 "int main() { if (1) return 2; return 3; }"
+assert 2 "int main() { if (2-1) return 2; return 3; }"
+assert 4 "int main() { int a = 0; int b; if (a) b = 3; else b = 4; b; }"
+assert 7 "int main() { int a = 1, b; if (a == 0) b = 5; else if (a == 1) b = 7; else b = 9; b; }"
+assert 9 "int main() { int a = 3, b; if (a < 2) if (a < 1) b = 1; else b = 2; else b = 9; b; }"
+assert 10 "int main() { int i = 0; while (i < 10) i = i + 1; return i; }"
+assert 55 "int main() { int i = 0; int j = 0; for (i = 0; i <= 10; i = i + 1) j = i + j; return j; }"
+assert 3 "int main() { for (;;) return 3; return 5; }"
+assert 1 "int main() { int i = 0; do i = i + 1; while (0); i; }"
+assert 5 "int main() { int i = 0; do i = i + 1; while (i < 5); i; }"
+assert 4 "int main() { int i = 0; while (1) if (i == 4) break; else i = i + 1; i; }"
+assert 7 "int main() { int i; for (i = 0; ; i = i + 1) if (i == 7) break; i; }"
+assert 25 "int main() { int i, j = 0; for (i = 0; i < 10; i = i + 1) if (i / 2 * 2 == i) continue; else j = j + i; j; }"
+assert 20 "int main() { int i = 0; int j = 0; while (i < 10) if ((i = i + 1) < 5) continue; else j = j + 4; j - 4; }"
+assert 3 "int main() { int i = 0; do if ((i = i + 1) < 3) continue; else break; while (1); i; }"
+assert 3 "int main() { { 1; { 2; } return 3; } }"
+assert 5 "int main() { { ; ; ; } return 5; }"
+assert 55 "int main() { int i = 0; int j = 0; while (i < 10) { i = i + 1; j = j + i; } return j; }"
+assert 10 "int main() { int a = 1; { a = 10; } return a; }"
+assert 7 "int main() { int a = 7; { int b = 3; } { int c = 4; } return a; }"
+assert 3 "int main() { int x = 0; for (int i = 0; i < 3; i = i + 1) { int t = i; x = x + 1; } return x; }"
+assert 4 "int sub(int a, int b) { return a - b; } int main() { return 4; }"
+assert 8 "int main() { return 8; } int one() { return 1; }"
+assert 6 "int f(int a, int b, int c, int d, int e, int f) { int x = a; return f; } int main() { int a = 6; return a; }"
+assert 3 "int main() { return ret3(); }"
+assert 5 "int main() { return ret5(); }"
+assert 8 "int main() { return plus(3, 5); }"
+assert 2 "int main() { return minus(5, 3); }"
+assert 21 "int main() { return add6(1, 2, 3, 4, 5, 6); }"
+assert 66 "int main() { return add6(1, 2, add6(3, 4, 5, 6, 7, 8), 9, 10, 11); }"
+assert 136 "int main() { return add6(1, 2, add6(3, add6(4, 5, 6, 7, 8, 9), 10, 11, 12, 13), 14, 15, 16); }"
+assert 20 "int main() { return sub8(100, 1, 2, 3, 4, 5, 6, 59); }"
+assert 9 "int main() { return at9(1, 2, 3, 4, 5, 6, 7, 8, 9); }"
+assert 1 "int main() { return aligned(); }"
+assert 1 "int main() { int a = 1; return a + aligned() - 1; }"
+assert 1 "int main() { return 1 + (2 * aligned()) - 2; }"
+assert 1 "int main() { return sub8(9, aligned(), 0, 0, 0, 0, 0, 7); }"
+assert 1 "int main() { return at9(0, 0, 0, 0, 0, 0, 0, 0, aligned()); }"
+assert 1 "int main() { return 10 - at9(0, 0, 0, 0, 0, 0, 0, 0, 9 + aligned() * 0); }"
+assert 3 "int main() { return print(123) - 1; }"
+assert 7 "int add2(int x, int y) { return x + y; } int main() { return add2(3, 4); }"
+assert 1 "int sub2(int x, int y) { return x - y; } int main() { return sub2(4, 3); }"
+assert 55 "int fib(int x) { if (x <= 1) return x; return fib(x - 1) + fib(x - 2); } int main() { return fib(10); }"
+assert 36 "int f(int a, int b, int c, int d, int e, int f, int g, int h) { return a + b + c + d + e + f + g + h; } int main() { return f(1, 2, 3, 4, 5, 6, 7, 8); }"
+assert 98 "int f(int a, int b, int c, int d, int e, int f, int g, int h, int i) { return h * 10 + i; } int main() { return f(1, 2, 3, 4, 5, 6, 7, 9, 8); }"
+assert 1 "int f(int a, int b, int c, int d, int e, int f, int g) { return aligned(); } int main() { return f(1, 2, 3, 4, 5, 6, 7); }"
+assert 3 "int main() { int a = 3; { int a = 5; } return a; }"
+assert 5 "int main() { int a = 3; { int a = 5; return a; } }"
+assert 8 "int main() { int a = 3; { int b = a + 5; return b; } }"
+assert 2 "int main() { int i = 2; for (int i = 0; i < 5; i = i + 1) 0; return i; }"
+assert 1 "int main() { char c = 1; return c; }"
+assert 3 "int main() { char a = 1, b = 2; return a + b; }"
+assert 1 "int main() { char c = 255; return c == -1; }"
+assert 0 "int main() { char c = 256; return c; }"
+assert 44 "int main() { short s = 300; char c = s; return c; }"
+assert 1 "int main() { short s = 65535; return s == -1; }"
+assert 1 "int main() { int i = 4294967295; return i == -1; }"
+assert 1 "int main() { long l = 4294967296; return l - 4294967295; }"
+assert 8 "int main() { long a = 3; short b = 2; int c = 1; char d = 2; return a + b + c + d; }"
+assert 7 "int main() { long x; long int y; long long z; short int w; x = 1; y = 2; z = 3; w = 1; return x + y + z + w; }"
+assert 3 "char f(char a, short b) { return a + b; } int main() { return f(1, 2); }"
+assert 1 "int f(long a, long b, long c, long d, long e, long f, char g, short h) { return g == -1; } int main() { return f(1, 2, 3, 4, 5, 6, 255, 7); }"
+assert 1 "int f(int a, int b, int c, int d, int e, int f, int g, char h, char i) { return i == -1; } int main() { return f(1, 2, 3, 4, 5, 6, 7, 8, 255); }"
+assert 3 "int main() { int x = 3; return *&x; }"
+assert 3 "int main() { int x = 3; int *y = &x; int **z = &y; return **z; }"
+assert 5 "int main() { int x = 3; int *y = &x; *y = 5; return x; }"
+assert 7 "int main() { int x = 3; int y = 5; *(&x + 1) = 7; return *(&x + 1); }"
+assert 2 "int main() { long x; long *p = &x; long *q = p + 2; return q - p; }"
+assert 8 "int main() { char c; short s; int i; long l; return (&i + 1) - &i + (&l + 2) - &l + (&c + 5) - &c; }"
+assert 1 "int main() { long x; long *p = &x; return p + 1 - 1 == p; }"
+assert 1 "int main() { char x = 1; char *p = &x; return *p; }"
+assert 9 "int set(int *p, int v) { *p = v; return 0; } int main() { int x; set(&x, 9); return x; }"
+assert 3 "int *id(int *p) { return p; } int main() { int x = 3; return *id(&x); }"
+assert 4 "int main() { int x = 1; int *p = &x; int *q = p + 1; return q - p + (p - q) + 4; }"
+assert 1 "int main() { int x; int *p = &x; return p + 1 > p; }"
+assert 3 "int main() { int a[2]; *a = 1; *(a + 1) = 2; int *p = a; return *p + *(p + 1); }"
+assert 6 "int main() { int a[3]; a[0] = 1; a[1] = 2; a[2] = 3; return a[0] + a[1] + a[2]; }"
+assert 2 "int main() { int a[3]; a[2] = 2; return 2[a]; }"
+assert 5 "int main() { int a[2][3]; a[1][2] = 5; return *(*(a + 1) + 2); }"
+assert 15 "int main() { int a[2][3]; int i; int j; for (i = 0; i < 2; i = i + 1) for (j = 0; j < 3; j = j + 1) a[i][j] = i + j; return a[0][0] + a[0][1] + a[0][2] + a[1][0] + a[1][1] + a[1][2] + 6; }"
+assert 10 "int main() { char s[10]; int n = 0; for (int i = 0; i < 10; i = i + 1) { s[i] = 1; } for (int i = 0; i < 10; i = i + 1) n = n + s[i]; return n; }"
+assert 1 "int main() { char a[5000]; a[4999] = 1; return a[4999]; }"
+assert 7 "int main() { char a[5000]; int x = 3; a[0] = 4; return a[0] + x; }"
+assert 9 "int f(int n) { long a[70000]; a[69999] = n; return a[69999]; } int main() { return f(9); }"
+assert 40 "int main() { int a[10]; return sizeof a; }"
+assert 1 "int main() { return sizeof(int) - 5 > 0; }"
+assert 1 "int main() { return _Alignof(char) - 2 > 0; }"
+assert 0 "int main() { int x = -1; return x < sizeof(x); }"
+assert_gcc "int main() { long n = sizeof(long) - 9; return (n > 0) + (sizeof(char) - 2 > 1) * 2; }"
+assert 24 "int main() { long a[3]; return sizeof(a); }"
+assert 24 "int main() { int a[2][3]; return sizeof a; }"
+assert 12 "int main() { int a[2][3]; return sizeof a[1]; }"
+assert 4 "int main() { int a[2][3]; return sizeof a[1][0]; }"
+assert 8 "int main() { int a[3]; return sizeof(a + 1); }"
+assert 8 "int main() { int x; return sizeof &x + sizeof x - 4; }"
+assert 1 "int main() { char c; return sizeof c; }"
+assert 6 "int sum(int *a, int n) { int s = 0; for (int i = 0; i < n; i = i + 1) s = s + a[i]; return s; } int main() { int a[3]; a[0] = 1; a[1] = 2; a[2] = 3; return sum(a, 3); }"
+assert 7 "int get(int a[4], int i) { return a[i]; } int main() { int a[4]; a[3] = 7; return get(a, 3); }"
+assert 3 "int main() { int a[4]; return &a[3] - a; }"
+assert 9 "int main() { int x = 9; int a[3]; a[0] = 0; a[1] = 0; a[2] = 0; { int y[5]; y[4] = 1; } return x; }"
+assert 4 "int main() { return sizeof(int); }"
+assert 1 "int main() { return sizeof(char); }"
+assert 2 "int main() { return sizeof(short int); }"
+assert 8 "int main() { return sizeof(long long); }"
+assert 8 "int main() { return sizeof(int *); }"
+assert 12 "int main() { return sizeof(int[3]); }"
+assert 24 "int main() { return sizeof(char *[3]); }"
+assert 4 "int main() { int x; return sizeof(x); }"
+assert 8 "int main() { int x; return sizeof(x) + sizeof(x + 1); }"
+assert 3 "int main() { int a[3]; return sizeof(a) / sizeof(a[0]); }"
+assert 4 "int main() { return _Alignof(int); }"
+assert 8 "int main() { return _Alignof(long *); }"
+assert 2 "int main() { return _Alignof(short[5]); }"
+assert 6 "int main() { int a[2 * 3]; return sizeof(a) / sizeof(int); }"
+assert 8 "int main() { char a[sizeof(long)]; return sizeof a; }"
+assert 5 "int main() { int a[(10 - 4) / 2 + 2]; a[4] = 5; return a[4]; }"
+assert 0 "int x; int main() { return x; }"
+assert 3 "int x; int main() { x = 3; return x; }"
+assert 7 "int x = 7; int main() { return x; }"
+assert 5 "int x = 2 + 3; int main() { return x; }"
+assert 1 "char c = -1; int main() { return c == -1; }"
+assert 1 "long l = 4294967296; int main() { return l == 4294967296; }"
+assert 44 "char c = 300; int main() { return c; }"
+assert 3 "int x, y = 2; int main() { x = 1; return x + y; }"
+assert 6 "int a[3]; int main() { a[0] = 1; a[1] = 2; a[2] = 3; return a[0] + a[1] + a[2]; }"
+assert 12 "int a[3]; int main() { return sizeof a; }"
+assert 5 "int x; int set() { x = 5; return 0; } int main() { set(); return x; }"
+assert 2 "int x = 1; int main() { int x = 2; return x; }"
+assert 1 "int x = 1; int main() { { int x = 2; } return x; }"
+assert 4 "int x; int *p; int main() { p = &x; *p = 4; return x; }"
+assert 9 "int *p = 0; int g = 9; int main() { p = &g; return *p; }"
+assert 97 "int main() { return 'a'; }"
+assert 10 "int main() { return '\\n'; }"
+assert 1 "int main() { return '\\xff' == -1; }"
+assert 65 "int main() { return '\\101'; }"
+assert 0 "int main() { return '\\0'; }"
+assert 39 "int main() { return '\\''; }"
+assert 4 "int main() { return sizeof(\"abc\"); }"
+assert 97 "int main() { return \"abc\"[0]; }"
+assert 99 "int main() { return \"abc\"[2]; }"
+assert 0 "int main() { return \"abc\"[3]; }"
+assert 98 "int main() { char *p = \"abc\"; return *(p + 1); }"
+assert 9 "int main() { return \"\\t\"[0]; }"
+assert 92 "int main() { return \"\\\\\"[0]; }"
+assert 65 "int main() { return \"\\x41\"[0]; }"
+assert 65 "int main() { return \"\\101\"[0]; }"
+assert 0 "int main() { return \"a\\0b\"[1]; }"
+assert 4 "int main() { return sizeof(\"a\\0b\"); }"
+assert 34 "int main() { return \"\\\"\"[0]; }"
+assert 1 "int main() { char *a = \"xy\"; char *b = \"xy\"; return a == b; }"
+assert 3 "int main() { return printf(\"hi\\n\"); }"
+assert 5 "int main() { int x = 42; return printf(\"%d %s\\n\", x, \"a\"); }"
+assert 3 "int main() { /* return 1; */ return 3; }"
+assert 2 "int main() { // return 1;
+return 2; }"
+assert 4 "/* header
+ * comment */
+int main() {
+    int a = 4; // a
+    return a /* / 2 */;
+} // end"
+assert 2 "int main() { return 4 / /* */ 2; }"
+assert 47 "int main() { return \"/*\"[0]; }"
+assert 1 "int main() { return 1; } /* trailing */"
+assert 1 "int main() { return 1 && 2; }"
+assert 0 "int main() { return 1 && 0; }"
+assert 0 "int main() { return 0 && 1; }"
+assert 1 "int main() { return 0 || 3; }"
+assert 0 "int main() { return 0 || 0; }"
+assert 1 "int main() { return 5 || 0; }"
+assert 0 "int main() { return !1; }"
+assert 1 "int main() { return !0; }"
+assert 0 "int main() { return !5; }"
+assert 1 "int main() { return !!7; }"
+assert 1 "int main() { int x = 0; return !x && 1; }"
+assert 1 "int main() { return 1 || 0 && 0; }"
+assert 0 "int main() { return (1 || 0) && 0; }"
+assert 1 "int main() { return 1 == 1 && 2 < 3; }"
+assert 0 "int x; int set() { x = 1; return 1; } int main() { 0 && set(); return x; }"
+assert 0 "int x; int set() { x = 1; return 1; } int main() { 1 || set(); return x; }"
+assert 1 "int x; int set() { x = 1; return 1; } int main() { 1 && set(); return x; }"
+assert 1 "int x; int set() { x = 1; return 1; } int main() { 0 || set(); return x; }"
+assert 1 "int main() { int *p = 0; return !p; }"
+assert 0 "int main() { int *p = 0; return p && *p; }"
+assert 3 "int main() { int a[!0 + (1 && 2) + (0 || 4)]; return sizeof a / sizeof a[0]; }"
+assert 1 "int main() { int i = 0; while (i < 10 && !(i == 5)) i = i + 1; return i == 5; }"
+assert 2 "int main() { return 17 % 5; }"
+assert 1 "int main() { return -7 % 3 == -1; }"
+assert 1 "int main() { return -7 / 2 == -3; }"
+assert 2 "int main() { return 6 & 3; }"
+assert 7 "int main() { return 6 | 3; }"
+assert 5 "int main() { return 6 ^ 3; }"
+assert 1 "int main() { return ~0 == -1; }"
+assert 250 "int main() { return ~5 & 255; }"
+assert 16 "int main() { return 1 << 4; }"
+assert 4 "int main() { return 16 >> 2; }"
+assert 1 "int main() { return -16 >> 2 == -4; }"
+assert 1 "int main() { return 1 << 31 < 0; }"
+assert 3 "int main() { return 1 + 2 & 7; }"
+assert 3 "int main() { return 1 | 2 ^ 3 & 5; }"
+assert 9 "int main() { return 1 << 3 | 1; }"
+assert 1 "int main() { return 3 < 1 << 2; }"
+assert 1 "int main() { return 7 & 3 == 3; }"
+assert 4 "int main() { unsigned int x = 4; return x; }"
+assert 4 "int main() { return sizeof(unsigned); }"
+assert 1 "int main() { return sizeof(unsigned char) + sizeof(signed char) - 1; }"
+assert 8 "int main() { return sizeof(unsigned long int); }"
+assert 255 "int main() { unsigned char c = 255; return c; }"
+assert 1 "int main() { unsigned char c = 255; return c == 255; }"
+assert 1 "int main() { signed char c = 255; return c == -1; }"
+assert 1 "int main() { unsigned short s = 65535; return s == 65535; }"
+assert 1 "int main() { unsigned int x = 0; x = x - 1; return x == 4294967295; }"
+assert 1 "int main() { unsigned int x = 4294967295; return x > 0; }"
+assert 0 "int main() { int x = -1; return x > 0; }"
+assert 1 "int main() { unsigned int x = 1; int y = -1; return y > x; }"
+assert 1 "int main() { unsigned int x = 4294967295; return (x >> 28) == 15; }"
+assert 1 "int main() { int x = -1; return (x >> 28) == -1; }"
+assert 1 "int main() { unsigned long x = 0; x = x - 1; return (x >> 60) == 15; }"
+assert 1 "int main() { unsigned int x = 4294967295; return x / 2 == 2147483647; }"
+assert 1 "int main() { unsigned int x = 4294967295; return x % 10 == 5; }"
+assert 1 "int main() { int x = 2147483647; x = x + 1; return x < 0; }"
+assert 1 "int main() { int x = 2147483647; return x + 1 < 0; }"
+assert 1 "int main() { unsigned int x = 1; return (x << 31) > 0; }"
+assert 1 "unsigned char uc(int x) { return x; } int main() { return uc(257); }"
+assert 44 "char c(int x) { return x; } int main() { return c(300); }"
+assert 1 "int main() { long x = 1; return (x << 40 >> 40) == 1; }"
+assert 1 "unsigned int g = 4294967295; int main() { return g == 4294967295; }"
+assert 1 "int main() { int a = -2; return a < 4294967295u; }"
+assert 1 "int main() { int a = -1; return a == 4294967295u; }"
+assert 1 "int main() { int a = -4; unsigned b = 2; return (a / b) == 2147483646; }"
+assert 1 "int main() { int x = -4; x /= 2u; return x == 2147483646; }"
+assert 1 "int main() { return -1 == 4294967295u; }"
+assert 4 "int main() { int a[-2 < 4294967295u ? 1 : 2]; return sizeof(a); }"
+assert 3 "int main() { int a[15 % 4]; return sizeof a / sizeof a[0]; }"
+assert 7 "int main() { int x = 5; x += 2; return x; }"
+assert 3 "int main() { int x = 5; x -= 2; return x; }"
+assert 10 "int main() { int x = 5; x *= 2; return x; }"
+assert 2 "int main() { int x = 5; x /= 2; return x; }"
+assert 1 "int main() { int x = 5; x %= 2; return x; }"
+assert 4 "int main() { int x = 6; x &= 5; return x; }"
+assert 7 "int main() { int x = 6; x |= 5; return x; }"
+assert 3 "int main() { int x = 6; x ^= 5; return x; }"
+assert 24 "int main() { int x = 6; x <<= 2; return x; }"
+assert 1 "int main() { int x = 6; x >>= 2; return x; }"
+assert 1 "int main() { int x = -6; x >>= 2; return x == -2; }"
+assert 1 "int main() { unsigned int x = 4294967295; x >>= 28; return x == 15; }"
+assert 8 "int main() { int x = 5; return x += 3; }"
+assert 6 "int main() { int x = 5; return ++x; }"
+assert 4 "int main() { int x = 5; return --x; }"
+assert 5 "int main() { int x = 5; return x++; }"
+assert 5 "int main() { int x = 5; return x--; }"
+assert 6 "int main() { int x = 5; x++; return x; }"
+assert 4 "int main() { int x = 5; x--; return x; }"
+assert 3 "int main() { int a[3]; a[0] = 1; a[1] = 2; a[2] = 3; int *p = a; p++; return *++p; }"
+assert 2 "int main() { int a[3]; a[0] = 1; a[1] = 2; a[2] = 3; int *p = a + 2; p -= 1; return *p; }"
+assert 1 "int main() { int a[3]; int *p = a; p += 2; return p - a == 2; }"
+assert 2 "int main() { int a[3]; a[0] = 1; a[1] = 2; int *p = a; return *(++p); }"
+assert 1 "int main() { int a[3]; a[0] = 1; a[1] = 2; int *p = a; return *p++; }"
+assert 255 "int main() { unsigned char c = 255; return c++; }"
+assert 0 "int main() { unsigned char c = 255; c++; return c; }"
+assert 0 "int main() { unsigned char c = 255; return ++c; }"
+assert 1 "int main() { char c = 127; c++; return c == -128; }"
+assert 1 "int main() { char c = 10; c += 250; return c == 4; }"
+assert 1 "int main() { long l = 1; l <<= 40; return l == 1099511627776; }"
+assert 1 "int n; int f() { n++; return 1; } int main() { int a[3]; a[1] = 0; a[f()] += 5; return n; }"
+assert 5 "int n; int f() { n++; return 1; } int main() { int a[3]; a[1] = 0; a[f()] += 5; return a[1]; }"
+assert 1 "int n; int f() { n++; return 1; } int main() { int a[3]; a[1] = 0; a[f()]++; return n; }"
+assert 10 "int main() { int i; int s = 0; for (i = 0; i < 5; i++) s += i; return s; }"
+assert 3 "int main() { int x = 0; int *p = &x; (*p)++; ++*p; *p += 1; return x; }"
+assert 2 "int main() { return 1 ? 2 : 3; }"
+assert 3 "int main() { return 0 ? 2 : 3; }"
+assert 4 "int main() { int x = 0; return x ? 3 : x == 0 ? 4 : 5; }"
+assert 5 "int main() { int x = 2; return x == 1 ? 3 : x == 3 ? 4 : 5; }"
+assert 1 "int n; int f() { n++; return 1; } int main() { int x = 1 ? 2 : f(); x = 0 ? f() : 3; return n == 0 && x == 3; }"
+assert 7 "int main() { int x = 3; int y = 4; (x > y ? x : y) == 4 ? x = 7 : 0; return x; }"
+assert 1 "int main() { unsigned int u = 0; return (1 ? -1 : u) > 0; }"
+assert 8 "int main() { long l; return sizeof(1 ? 1 : l); }"
+assert 4 "int main() { char c = 1; return sizeof(0 ? c : c); }"
+assert 2 "int main() { int a[2]; a[0] = 1; a[1] = 2; int *p = 0 ? a : a + 1; return *p; }"
+assert 1 "int main() { int x; int *p = 1 ? 0 : &x; return p == 0; }"
+assert 8 "int main() { int a[2]; return sizeof(1 ? a : a); }"
+assert 3 "int main() { return (1, 2, 3); }"
+assert 5 "int main() { int x; int y; x = (y = 2, y + 3); return x; }"
+assert 1 "int n; int f() { n++; return 1; } int main() { f(), n = 0, f(); return n; }"
+assert 8 "int main() { int a[2]; return sizeof(0, a); }"
+assert 15 "int main() { int i; int j; int s = 0; for (i = 0, j = 5; i < j; i++, j--) s += i + j; return s; }"
+assert 3 "int main() { return plus(1, 2), 3; }"
+assert 11 "int main() { int x = 1; switch (x) { case 0: return 10; case 1: return 11; } return 12; }"
+assert 12 "int main() { int x = 2; switch (x) { case 0: return 10; case 1: return 11; } return 12; }"
+assert 13 "int main() { int x = 2; switch (x) { case 0: return 10; default: return 13; case 1: return 11; } return 12; }"
+assert 6 "int main() { int x = 1; int y = 0; switch (x) { case 1: y += 1; case 2: y += 2; case 3: y += 3; break; case 4: y += 4; } return y; }"
+assert 3 "int main() { int x = 5; int y = 0; switch (x) { default: y = 1; case 2: y += 2; break; case 3: y = 9; } return y; }"
+assert 18 "int main() { int i; int s = 0; for (i = 0; i < 5; i++) { switch (i) { case 1: continue; case 3: s += 10; break; } s += 2; } return s; }"
+assert 9 "int main() { int x = 2; int y = 3; switch (x) { case 2: switch (y) { case 3: x = 9; break; } break; case 9: x = 1; } return x; }"
+assert 1 "int main() { char c = -1; switch (c) { case -1: return 1; case 255: return 2; } return 3; }"
+assert 2 "int main() { unsigned char c = 255; switch (c) { case -1: return 1; case 255: return 2; } return 3; }"
+assert 1 "int main() { unsigned int u = 4294967295; switch (u) { case -1: return 1; } return 2; }"
+assert 1 "int main() { long l = 1099511627776; switch (l) { case 1099511627776: return 1; case 1: return 2; } return 3; }"
+assert 5 "int main() { int x = 3; switch (x) case 3: x = 5; return x; }"
+assert 0 "int main() { switch (1) { } return 0; }"
+assert 7 "int main() { switch (3) { case 1 + 2: return 7; } return 0; }"
+assert 236 "int f(int x) { switch (x) { case 0: return 10; case 1: return 11; case 2: case 3: return 13; case 5: return 15; default: return 99; } } int main() { return f(0) + f(3) + f(4) + f(5) + f(-1); }"
+assert 50 "int f(int x) { switch (x) { case -2: return 1; case -1: return 2; case 0: return 3; case 1: return 4; } return 20; } int main() { return f(-2) + f(-1) + f(0) + f(1) + f(2) + f(-3); }"
+assert 43 "int f(int x) { int y = 0; switch (x) { case 10: y = 1; case 11: y += 2; break; case 12: y = 30; case 13: y += 4; break; } return y; } int main() { return f(10) + f(11) + f(12) + f(13) + f(14); }"
+assert 3 "int f(unsigned int x) { switch (x) { case 0: return 0; case 1: return 1; case 2: return 2; case 4294967295: return 3; case 3: return 4; } return 5; } int main() { return f(-1); }"
+assert 10 "int main() { int i; int s = 0; for (i = 0; i < 8; i++) switch (i) { case 0: case 1: case 2: case 3: s++; break; case 4: case 5: s += 3; } return s; }"
+assert 3 "int main() { int x = 1; goto end; x = 2; end: x += 2; return x; }"
+assert 10 "int main() { int i = 0; loop: i++; if (i < 10) goto loop; return i; }"
+assert 7 "int main() { int x = 0; goto a; b: x += 4; goto c; a: x += 3; goto b; c: return x; }"
+assert 5 "int main() { int x = 0; { inner: x++; } if (x < 5) goto inner; return x; }"
+assert 1 "int f() { goto end; end: return 1; } int main() { goto end; end: return f(); }"
+assert 4 "int main() { int i; int s = 0; for (i = 0; i < 10; i++) { if (i == 4) goto out; s++; } out: return s; }"
+assert 2 "int main() { int x = 2; switch (x) { case 1: goto one; case 2: goto two; } one: return 1; two: return 2; }"
+assert 3 "int main() { int end = 1; end: end += 2; return end; }"
+assert 6 "int cleanup() { return 6; } int main() { goto cleanup; return 0; cleanup: return cleanup(); }"
+assert 31 "int main() { return 0x1f; }"
+assert 255 "int main() { return 0XFF; }"
+assert 15 "int main() { return 017; }"
+assert 0 "int main() { return 00; }"
+assert 5 "int main() { return 0b101; }"
+assert 10 "int main() { return 0B1010; }"
+assert 4 "int main() { return sizeof(1); }"
+assert 8 "int main() { return sizeof(1L); }"
+assert 8 "int main() { return sizeof(1ll); }"
+assert 4 "int main() { return sizeof(1u); }"
+assert 8 "int main() { return sizeof(1UL); }"
+assert 8 "int main() { return sizeof(2147483648); }"
+assert 4 "int main() { return sizeof(0x80000000); }"
+assert 8 "int main() { return sizeof(0x100000000); }"
+assert 1 "int main() { return -1 < 0; }"
+assert 0 "int main() { return -1 < 0u; }"
+assert 0 "int main() { return 0x80000000 < 0; }"
+assert 1 "int main() { return 0xffffffff == 4294967295u; }"
+assert 1 "int main() { return 0xffffffffffffffff > 0; }"
+assert 1 "int main() { return 18446744073709551615u == -1; }"
+assert 1 "int main() { return 0x7fffffffffffffff + 1 < 0; }"
+assert 1 "int main() { return (0xffffffff >> 31) == 1; }"
+assert 1 "int main() { long x = 1099511627776L; return x == 0x10000000000; }"
+assert 3 "int main() { int x = 3; switch (x) { case 0x3: return 3; } return 0; }"
+assert 3 "int main() { struct { int a; int b; } x; x.a = 1; x.b = 2; return x.a + x.b; }"
+assert 8 "int main() { struct { int a; int b; } x; return sizeof(x); }"
+assert 16 "int main() { struct { char a; long b; } x; return sizeof(x); }"
+assert 12 "int main() { struct { char a; int b; char c; } x; return sizeof(x); }"
+assert 8 "int main() { struct { char a; int b; } x; return sizeof x; }"
+assert 3 "int main() { struct { char a; char b; char c; } x; return sizeof(x); }"
+assert 4 "int main() { struct { short a; char b; } x; return sizeof(x); }"
+assert 4 "int main() { struct { char a; int b; } x; return _Alignof(struct { char a; int b; }); }"
+assert 0 "int main() { struct {} x; return sizeof(x); }"
+assert 24 "int main() { struct point { char c; int x; long y; } p; return sizeof(struct point) + p.x * 0 + 8; }"
+assert 5 "int main() { struct s { int a; int b; }; struct s x; x.b = 5; return x.b; }"
+assert 7 "struct s { int a; int b; }; int main() { struct s x; x.a = 7; return x.a; }"
+assert 7 "struct s { int a; int b; } g; int main() { g.a = 7; return g.a; }"
+assert 1 "int main() { struct { char a; int b; } x; char *p = &x.b; char *q = &x; return p - q == 4; }"
+assert 6 "int main() { struct { int a; struct { char b; int c; } y; } x; x.a = 1; x.y.b = 2; x.y.c = 3; return x.a + x.y.b + x.y.c; }"
+assert 12 "int main() { struct { int a; struct { char b; int c; } y; } x; return sizeof(x); }"
+assert 10 "int main() { struct { int a[3]; int b; } x; x.a[0] = 1; x.a[2] = 2; x.b = 7; return x.a[0] + x.a[2] + x.b; }"
+assert 9 "int main() { struct { int a; int b; } x[3]; x[0].a = 1; x[1].b = 3; x[2].a = 5; return x[0].a + x[1].b + x[2].a; }"
+assert 24 "int main() { struct { int a; int b; } x[3]; return sizeof(x); }"
+assert 3 "int main() { struct s { int a; int b; } x; struct s *p = &x; p->b = 3; return x.b; }"
+assert 4 "int main() { struct s { int a; int b; } x; struct s *p = &x; x.a = 4; return (*p).a; }"
+assert 2 "int main() { struct s { int a; int b; } x[2]; struct s *p = x; p++; p->a = 2; return x[1].a; }"
+assert 8 "int main() { struct s { int a; int b; } x[2]; char *p = x + 1; char *q = x; return p - q; }"
+assert 3 "int main() { struct node { int val; struct node *next; } a; struct node b; a.val = 1; a.next = &b; b.val = 2; b.next = 0; return a.val + a.next->val; }"
+assert 15 "int main() { struct node { int val; struct node *next; } n[3]; int i; for (i = 0; i < 3; i++) { n[i].val = i + 4; n[i].next = i < 2 ? &n[i + 1] : 0; } struct node *p; int s = 0; for (p = n; p; p = p->next) s += p->val; return s; }"
+assert 3 "int main() { struct s { int a; int b; } x; struct s y; x.a = 1; x.b = 2; y = x; return y.a + y.b; }"
+assert 1 "int main() { struct s { int a; int b; } x; struct s y; x.a = 1; y = x; x.a = 5; return y.a; }"
+assert 7 "int main() { struct s { char a[7]; } x; struct s y; x.a[0] = 1; x.a[6] = 6; y = x; return y.a[0] + y.a[6]; }"
+assert 6 "int main() { struct s { char a; short b; char c; long d; } x; struct s y; x.a = 1; x.b = 2; x.c = 3; x.d = 0; y = x; return y.a + y.b + y.c + y.d; }"
+assert 2 "int main() { struct s { int a; } x; struct s y; struct s z; x.a = 2; z = y = x; return z.a; }"
+assert 5 "int main() { struct s { int a; struct { int b; } in; } x; struct s y; x.in.b = 5; y.in = x.in; return y.in.b; }"
+assert 3 "int main() { struct s { int a; } x; struct s y; x.a = 2; y.a = 3; struct s z = 0 ? x : y; return z.a; }"
+assert 4 "struct s { int a; int b; } g; int main() { struct s x; x.b = 4; g = x; return g.b; }"
+assert 2 "int main() { struct s { int a; } x; x.a = 1; { struct s { int b; int c; } y; y.c = 2; return sizeof(y) / 4; } }"
+assert 4 "int main() { struct s { int a; }; { struct s { long b; }; } return sizeof(struct s); }"
+assert 1 "int main() { struct s *p; struct s { int a; } x; p = &x; x.a = 1; return p->a; }"
+assert 3 "int main() { struct s { int x; } s; s.x = 3; return s.x; }"
+assert 2 "int main() { struct s { int a; int b; } x; x.a = 1; x.b = 1; x.a++; x.b += x.a; return x.a; }"
+assert 6 "int main() { struct s { int a; int b; } x; struct s *p = &x; p->a = 1; p->b = 2; p->a += 3; p->b++; return p->a + p->b - 1; }"
+assert 8 "int main() { union { int a; char b; long c; } x; return sizeof(x); }"
+assert 8 "int main() { union { int a; char b; long c; } x; return _Alignof(union { int a; char b; long c; }); }"
+assert 6 "int main() { union { char a[5]; short b; } x; return sizeof(x); }"
+assert 2 "int main() { union { char a[5]; short b; } x; return _Alignof(union { char a[5]; short b; }); }"
+assert 12 "int main() { union { int a[3]; char b; } x; return sizeof(x); }"
+assert 1 "int main() { union { int a; char b; } x; char *p = &x.a; char *q = &x.b; return p == q; }"
+assert 3 "int main() { union { int a; char b; } x; x.a = 3; return x.b; }"
+assert 7 "int main() { union u { int a; int b; } x; union u *p = &x; p->a = 7; return p->b; }"
+assert 1 "int main() { union { int a; char b[4]; } x; x.a = 0x01020304; return x.b[0] == 4 && x.b[1] == 3 && x.b[2] == 2 && x.b[3] == 1; }"
+assert 16 "int main() { struct { char c; union { int a; long b; } u; } x; return sizeof(x); }"
+assert 5 "int main() { struct { char c; union { int a; long b; } u; } x; x.u.b = 5; return x.u.a; }"
+assert 9 "int main() { union u { struct { int a; int b; } s; long l; } x; union u y; x.s.a = 4; x.s.b = 5; y = x; return y.s.a + y.s.b; }"
+assert 8 "union u { int a; long b; } g; int main() { g.b = 8; return g.a; }"
+assert_gcc "int main() { union { int a; char b[4]; } x; x.a = 0x11223344; return x.b[0] + x.b[3]; }"
+assert_gcc "int main() { union { long l; unsigned char b[8]; } x; x.l = -2; return x.b[0] + x.b[7]; }"
+assert_gcc "int main() { union { short s; unsigned char b[2]; } x; x.s = 0x1234; return x.b[0] - x.b[1] + 0x40; }"
+assert_gcc "int main() { union { unsigned int u; int i; char c; } x; x.i = -1; return (x.u == 4294967295u) + x.c + 100; }"
+assert_gcc "int main() { union { long l; int i[2]; short s[4]; } x; x.l = 0x0102030405060708; return x.i[1] + x.s[1] + x.s[3]; }"
+assert_gcc "int main() { union { char c[6]; int i; } x; x.i = 0; x.c[4] = 9; x.c[1] = 1; return x.i / 256 + sizeof(x); }"
+assert 6 "int main() { enum Color { RED, GREEN = 5, BLUE }; return RED + BLUE; }"
+assert 4 "int main() { enum Color { RED, GREEN } c; return sizeof(c) * (sizeof(enum Color) == 4); }"
+assert 1 "int main() { enum { A = -2, B, C, D, }; return D; }"
+assert 3 "int main() { enum { A = 1 << 2, B = A - 1 }; return B; }"
+assert 24 "int main() { enum { N = 3 }; long a[N]; return sizeof(a); }"
+assert 20 "int main() { enum E { X, Y } e = Y; switch (e) { case X: return 10; case Y: return 20; } return 0; }"
+assert 7 "enum { SEVEN = 7 }; int g = SEVEN; int main() { return g; }"
+assert 2 "enum E { A = 2 }; int main() { enum E e = A; return e; }"
+assert 9 "enum { A = 1 }; int main() { int A = 9; return A; }"
+assert 5 "int main() { int A = 1; { enum { A = 5 }; return A; } }"
+assert 3 "int main() { enum { A = 1 }; { enum { A = 2 }; } return A + 2; }"
+assert 1 "int main() { enum E { A }; { struct E { int x; } s; s.x = 1; return s.x; } }"
+assert_gcc "int main() { enum { A = 10, B = A * 3, C = B / 4, D }; return A + B + C + D; }"
+assert_gcc "int main() { enum { A = 0x7fffffff, B = -0x7fffffff - 1 }; return (A > 0) + (B < 0) * 2; }"
+assert 3 "int main() { typedef int T; T x = 3; return x; }"
+assert 8 "int main() { typedef long T; T x; return sizeof(x); }"
+assert 8 "typedef long T; int main() { return sizeof(T); }"
+assert 6 "int main() { typedef int T; T *p; T x = 6; p = &x; return *p; }"
+assert 12 "int main() { typedef int T; { int T = 3; return T * 4; } }"
+assert 5 "int main() { int T = 5; { typedef int T; T x = 1; } return T; }"
+assert 4 "int main() { int T = 1; { typedef int T; T *x; T y = 3; x = &y; } return T + 3; }"
+assert 2 "int main() { typedef char T; { typedef short T; return sizeof(T); } }"
+assert 1 "int main() { typedef char T; { typedef short T; } return sizeof(T); }"
+assert 24 "int main() { typedef int A[3]; A x[2]; return sizeof(x); }"
+assert 3 "int main() { typedef struct { int a; int b; } P; P p; p.a = 1; p.b = 2; return p.a + p.b; }"
+assert 7 "typedef struct node Node; struct node { int v; Node *next; }; int main() { Node a; Node b; a.next = &b; b.v = 7; return a.next->v; }"
+assert 9 "typedef int T, *PT; int main() { T x = 9; PT p = &x; return *p; }"
+assert 8 "typedef int T; int f(T a, T b) { return a * b; } int main() { T x = 2; return f(x, 4); }"
+assert 2 "int main() { typedef enum { A, B, C } E; E e = C; return e; }"
+assert 3 "int main() { typedef int T; goto T; return 1; T: return 3; }"
+assert 6 "int main() { typedef int T; int a = 2; int b = 3; { int T = a; return T * b; } }"
+assert 4 "int main() { typedef int T; T x = 2; for (T i = 0; i < 2; i++) x++; return x; }"
+assert_gcc "typedef unsigned char u8; int main() { u8 x = 250; x = x + 10; return x; }"
+assert_gcc "typedef short s16; typedef s16 T; int main() { T a[5]; return sizeof(a) + _Alignof(T); }"
+assert 7 "int add(int a, int b) { return a + b; } int main() { int (*fp)(int, int) = add; return fp(3, 4); }"
+assert 7 "int add(int a, int b) { return a + b; } int main() { int (*fp)(int, int) = &add; return (*fp)(3, 4); }"
+assert 6 "int add(int a, int b) { return a + b; } int main() { return (add)(2, 4); }"
+assert 6 "int add(int a, int b) { return a + b; } int main() { return (**add)(2, 4); }"
+assert 12 "int add(int a, int b) { return a + b; } int mul(int a, int b) { return a * b; } int main() { int (*ops[2])(int, int); ops[0] = add; ops[1] = mul; return ops[0](1, 2) * ops[1](2, 2); }"
+assert 9 "int sq(int x) { return x * x; } int apply(int (*f)(int), int x) { return f(x); } int main() { return apply(sq, 3); }"
+assert 9 "int sq(int x) { return x * x; } int apply(int f(int), int x) { return f(x); } int main() { return apply(sq, 3); }"
+assert 4 "int one(void) { return 1; } int two(void) { return 2; } int main() { int (*fp)(void) = 1 ? two : one; return fp() * 2; }"
+assert 21 "int sum(int a, int b, int c, int d, int e, int f, int g, int h) { return a + b + c + d + e + f + g + h; } int main() { int (*fp)(int, int, int, int, int, int, int, int) = sum; return fp(1, 1, 2, 2, 3, 3, 4, 5); }"
+assert 5 "int five() { return 5; } int (*gfp)(void); int main() { gfp = five; return gfp(); }"
+assert 1 "int f() { return 0; } int main() { int (*fp)() = f; return fp == f && fp == &f; }"
+assert 8 "int main() { int (*fp)(int, int); return sizeof(fp); }"
+assert 8 "int main() { int (*a)[3]; return sizeof(a); }"
+assert 12 "int main() { int (*a)[3]; return sizeof(*a); }"
+assert 5 "int main() { int x[2][3]; int (*p)[3] = x; p[1][2] = 5; return x[1][2]; }"
+assert 32 "int main() { char *(*tbl[4])(void); return sizeof(tbl); }"
+assert 98 "char *s() { return \"ab\"; } int main() { char *(*tbl[4])(void); tbl[2] = s; return tbl[2]()[1]; }"
+assert 24 "int main() { return sizeof(int *[3]); }"
+assert 8 "int main() { return sizeof(int (*)[3]); }"
+assert 12 "int main() { return sizeof(int [3]); }"
+assert 8 "int main() { return sizeof(int (*)(int)); }"
+assert 8 "int main() { return _Alignof(char *(*)(void)); }"
+assert 7 "typedef int (*Op)(int); int inc(int x) { return x + 1; } Op get() { return inc; } int main() { return get()(6); }"
+assert 3 "typedef int F(int); int dec(int x) { return x - 1; } int main() { F *fp = dec; return fp(4); }"
+assert 3 "struct s { int (*f)(int); }; int dec(int x) { return x - 1; } int main() { struct s v; v.f = dec; return v.f(4); }"
+assert 44 "int main() { return (char)300; }"
+assert 255 "int main() { int x = -1; return (unsigned char)x; }"
+assert 1 "int main() { long x = 4294967297; return (int)x; }"
+assert 2 "int main() { int a[3]; a[2] = 2; long p = (long)a; return *(int *)(p + 8); }"
+assert 1 "int main() { return (long)(char *)8 == 8; }"
+assert 3 "int main() { int x = 3; int *p = &x; return *(int *)(char *)p; }"
+assert 4 "int main() { typedef int T; T x = 260; return (unsigned char)(T)x; }"
+assert_gcc "int main() { int a = -1; return ((unsigned)a >> 28) + (short)65537 + (unsigned short)-2 % 100; }"
+assert_gcc "int add(int a, int b) { return a + b; } int sub(int a, int b) { return a - b; } int main() { int (*ops[2])(int, int); ops[0] = add; ops[1] = sub; int r = 0; for (int i = 0; i < 2; i++) r = r * 10 + ops[i](7, 3); return r; }"
+assert_gcc "int twice(int (*f)(int), int x) { return f(f(x)); } int inc(int x) { return x + 1; } int main() { return twice(inc, 40); }"
+
+assert_error() {
+    input="$1"
+
+    echo "$input" > $MAINC
+    if $IOC $MAINC $MAINS 2> /dev/null; then
+        echo "$input => error expected, but compiled" >&2
+        exit 1
+    else
+        echo "$input => error"
+    fi
+}
+
+assert_error "int main() { break; }"
+assert_error "int main() { if (1) continue; }"
+assert_error "int main() { return 0;"
+assert_error "int main() { return plus(1, 2; }"
+assert_error "int main() { a = 1; return a; }"
+assert_error "int main() { { int a = 1; } return a; }"
+assert_error "int main() { for (int i = 0; i < 3; i = i + 1) 0; return i; }"
+assert_error "main() { return 0; }"
+assert_error "int main() { short char c; return 0; }"
+assert_error "int main() { int x; return *x; }"
+assert_error "int main() { int x; int *p = &x; return p + p; }"
+assert_error "int main() { int x; return &1; }"
+assert_error "int main() { 1 = 2; }"
+assert_error "int main() { int a[2]; int b[2]; a = b; }"
+assert_error "int main() { int x; return x[0]; }"
+assert_error "int main() { int a[2; return 0; }"
+assert_error "int main() { int n = 3; int a[n]; return 0; }"
+assert_error "int main() { int a[1 - 2]; return 0; }"
+assert_error "int main() { int a[1 / 0]; return 0; }"
+assert_error "int main() { return _Alignof 1; }"
+assert_error "int x; int x; int main() { return 0; }"
+assert_error "int y; int x = y; int main() { return 0; }"
+assert_error "int a[2] = 1; int main() { return 0; }"
+assert_error "int main() { return \"abc; }"
+assert_error "int main() { return 'a; }"
+assert_error "int main() { return ''; }"
+assert_error "int main() { return 'ab'; }"
+assert_error "int main() { return \"\\q\"[0]; }"
+assert_error "int main() { return 1; } /* unterminated"
+assert_error "int main() { /* return 1; }"
+assert_error "int main() { return 1 + /* x"
+assert_error "int main() { signed unsigned x; return 0; }"
+assert_error "int main() { int a[5 % 0]; return 0; }"
+assert_error "int main() { 1++; return 0; }"
+assert_error "int main() { int a[2]; a += 1; return 0; }"
+assert_error "int main() { int x; int *p = &x; p *= 2; return 0; }"
+assert_error "int main() { return 1 ? 2; }"
+assert_error "int main() { int x; char c; return *(1 ? &x : &c); }"
+assert_error "int main() { int x; return *(1 ? &x : 1); }"
+assert_error "int main() { int x; (1 ? x : x) = 3; return 0; }"
+assert_error "int main() { case 1: return 0; }"
+assert_error "int main() { default: return 0; }"
+assert_error "int main() { switch (1) { case 1: case 1: return 0; } }"
+assert_error "int main() { switch (1) { case 1: case 2 - 1: return 0; } }"
+assert_error "int main() { switch (1) { default: default: return 0; } }"
+assert_error "int main() { int x; switch (1) { case x: return 0; } }"
+assert_error "int main() { unsigned int u; switch (u) { case 4294967295: case -1: return 0; } }"
+assert_error "int main() { int x; switch (&x) { } return 0; }"
+assert_error "int main() { switch (1) { case 1 return 0; } }"
+assert_error "int main() { goto nowhere; return 0; }"
+assert_error "int main() { a: a: return 0; }"
+assert_error "int main() { a: return 0; } int f() { goto a; }"
+assert_error "int main() { goto 1; }"
+assert_error "int main() { return 1.2.3; }"
+assert_error "int main() { return 08; }"
+assert_error "int main() { return 0x; }"
+assert_error "int main() { return 0b2; }"
+assert_error "int main() { return 12abc; }"
+assert_error "int main() { return 1uu; }"
+assert_error "int main() { return 99999999999999999999; }"
+assert_error "int main() { return 9223372036854775808; }"
+assert_error "int main() { struct s x; return 0; }"
+assert_error "int main() { struct s { int a; } x; return x.b; }"
+assert_error "int main() { int x; return x.a; }"
+assert_error "int main() { struct s { int a; } x; return x->a; }"
+assert_error "int main() { struct s { int a; int a; } x; return 0; }"
+assert_error "int main() { struct s { int a; }; struct s { int b; }; return 0; }"
+assert_error "int main() { struct s { struct s x; }; return 0; }"
+assert_error "int main() { struct s { int a; } x; return x + 1; }"
+assert_error "int main() { struct s { int a; } x; if (x) return 1; return 0; }"
+assert_error "int main() { struct s { int a; } x; x = 1; return 0; }"
+assert_error "int main() { struct s { int a; } x; struct t { int a; } y; x = y; return 0; }"
+assert_error "int main() { struct s { int a; } x; return x; }"
+assert_error "int main() { struct s { int a; } x; x++; return 0; }"
+assert_error "int main() { struct s { int a; } x; return !x; }"
+assert_error "int main() { return sizeof(struct s); }"
+assert_error "int f(struct s { int a; } x) { return 0; } int main() { return 0; }"
+assert_error "struct s { int a; } g = 1; int main() { return 0; }"
+assert_error "int main() { struct s { int a; }; union s x; return 0; }"
+assert_error "int main() { union s { int a; }; struct s { int a; } x; return 0; }"
+assert_error "int main() { union u x; return 0; }"
+assert_error "int main() { union { int a; } x; return x * 2; }"
+assert_error "int main() { union { int a; } x; return x.b; }"
+assert_error "int main() { enum { A, A }; return 0; }"
+assert_error "int main() { int A; enum { A }; return 0; }"
+assert_error "int main() { enum { A = 0x80000000 }; return 0; }"
+assert_error "int main() { enum E { A }; struct E x; return 0; }"
+assert_error "int main() { struct E { int a; }; enum E x; return 0; }"
+assert_error "int main() { enum E { A }; enum E { B }; return 0; }"
+assert_error "int main() { enum { A }; A = 1; return 0; }"
+assert_error "int main() { enum E x; return 0; }"
+assert_error "int main() { int a; enum { A = a }; return 0; }"
+assert_error "int main() { typedef int T; typedef long T; return 0; }"
+assert_error "int main() { int T; typedef int T; return 0; }"
+assert_error "int main() { { typedef int T; } T x; return 0; }"
+assert_error "int main() { typedef int T; return T; }"
+assert_error "typedef int T; int T; int main() { return 0; }"
+assert_error "int main() { int x; return x(1); }"
+assert_error "int f() { return 0; } int main() { f = 0; return 0; }"
+assert_error "int main() { int a[3](int); return 0; }"
+assert_error "int main() { return sizeof(int (x)); }"
+assert_error "int main() { struct s { int a; } v; return (int)v; }"
+assert_error "int main() { int x; return (struct s { int a; })x; }"
+assert_error "int f() { return 0; } int main() { return sizeof(f); }"
+assert_error "int main() { int a; int a; return 0; }"
+assert_error "int main() { typedef int T; int T = 3; return T * 4; }"
+assert_error "int f(int a, int a) { return a; } int main() { return 0; }"
 
 echo OK